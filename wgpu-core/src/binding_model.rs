@@ -3,10 +3,12 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 use crate::{
-    device::SHADER_STAGE_COUNT,
-    id::{BindGroupLayoutId, BufferId, DeviceId, SamplerId, TextureViewId},
+    conv,
+    id::{BindGroupLayoutId, BufferId, DeviceId, SamplerId, TextureId, TextureViewId},
     track::{TrackerSet, DUMMY_SELECTOR},
+    validation::{check_texture_usage, MissingTextureUsageError},
     FastHashMap, LifeGuard, MultiRefCount, RefCount, Stored, MAX_BIND_GROUPS,
+    MAX_PUSH_CONSTANT_RANGES,
 };
 
 use arrayvec::ArrayVec;
@@ -17,14 +19,22 @@ use serde::Deserialize;
 #[cfg(feature = "trace")]
 use serde::Serialize;
 
-use std::{borrow::Borrow, ops::Range};
+use std::{
+    borrow::Borrow,
+    hash::{Hash, Hasher},
+    ops::Range,
+};
 
 use thiserror::Error;
 
 #[derive(Clone, Debug, Error)]
 pub enum BindGroupLayoutError {
-    #[error("conflicting binding at index {0}")]
-    ConflictBinding(u32),
+    #[error("binding {binding} is declared twice, as {existing:?} and as {incoming:?}")]
+    ConflictBinding {
+        binding: u32,
+        existing: wgt::BindingType,
+        incoming: wgt::BindingType,
+    },
     #[error("required device feature is missing: {0:?}")]
     MissingFeature(wgt::Features),
     #[error("arrays of bindings can't be 0 elements long")]
@@ -33,6 +43,96 @@ pub enum BindGroupLayoutError {
     ArrayUnsupported,
     #[error(transparent)]
     TooManyBindings(BindingTypeMaxCountError),
+    #[error("binding {binding} is an array of {count} elements, which exceeds the device limit of {limit}")]
+    ArrayCountExceedsLimit {
+        binding: u32,
+        count: u32,
+        limit: u32,
+    },
+    #[error("binding {0} has a dynamic offset and can't be an array")]
+    DynamicArrayUnsupported(u32),
+    #[error("binding {binding} is declared with different types across the layouts")]
+    InconsistentSharedBinding { binding: u32 },
+}
+
+/// Checks that the layouts used for the same bind group by different pipelines
+/// agree on the type of every binding they share.
+pub fn check_layout_set_consistency(layouts: &[&BindEntryMap]) -> Result<(), BindGroupLayoutError> {
+    for (i, layout) in layouts.iter().enumerate() {
+        for other in &layouts[i + 1..] {
+            for (binding, entry) in layout.iter() {
+                match other.get(binding) {
+                    Some(other_entry) if other_entry.ty != entry.ty => {
+                        return Err(BindGroupLayoutError::InconsistentSharedBinding {
+                            binding: *binding,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the binding type of a bind group layout entry is enabled by the
+/// device features.
+pub(crate) fn check_binding_type_feature(
+    entry: &wgt::BindGroupLayoutEntry,
+    features: wgt::Features,
+) -> Result<(), BindGroupLayoutError> {
+    match entry.ty {
+        wgt::BindingType::StorageTexelBuffer { .. }
+            if !features.contains(wgt::Features::TEXEL_BUFFERS) =>
+        {
+            Err(BindGroupLayoutError::MissingFeature(
+                wgt::Features::TEXEL_BUFFERS,
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Validates the `count` of a bind group layout entry against the device
+/// features and limits.
+pub(crate) fn check_binding_count(
+    entry: &wgt::BindGroupLayoutEntry,
+    features: wgt::Features,
+    limits: &wgt::Limits,
+) -> Result<(), BindGroupLayoutError> {
+    let count = match entry.count {
+        Some(count) => count,
+        None => return Ok(()),
+    };
+    if count == 0 {
+        return Err(BindGroupLayoutError::ZeroCount);
+    }
+    match entry.ty {
+        wgt::BindingType::UniformBuffer { dynamic: true, .. }
+        | wgt::BindingType::StorageBuffer { dynamic: true, .. }
+            if count > 1 =>
+        {
+            return Err(BindGroupLayoutError::DynamicArrayUnsupported(entry.binding))
+        }
+        wgt::BindingType::SampledTexture { .. } => {
+            if !features.contains(wgt::Features::SAMPLED_TEXTURE_BINDING_ARRAY) {
+                return Err(BindGroupLayoutError::MissingFeature(
+                    wgt::Features::SAMPLED_TEXTURE_BINDING_ARRAY,
+                ));
+            }
+            let limit = limits.max_sampled_textures_per_shader_stage;
+            if count > limit {
+                return Err(BindGroupLayoutError::ArrayCountExceedsLimit {
+                    binding: entry.binding,
+                    count,
+                    limit,
+                });
+            }
+        }
+        // No feature enables arrays of samplers, or of plain buffers.
+        _ => return Err(BindGroupLayoutError::ArrayUnsupported),
+    }
+    Ok(())
 }
 
 #[derive(Clone, Debug, Error)]
@@ -41,19 +141,127 @@ pub enum CreateBindGroupError {
     BindingsNumMismatch { actual: usize, expected: usize },
     #[error("unable to find a corresponding declaration for the given binding {0}")]
     MissingBindingDeclaration(u32),
-    #[error("binding {binding} has a different type ({actual:?}) than the one in the layout ({expected:?})")]
+    #[error("binding {0} is given more than once")]
+    DuplicateBinding(u32),
+    #[error(
+        "binding {binding} has a different type ({actual}) than the one in the layout ({expected})"
+    )]
     WrongBindingType {
         // Index of the binding
         binding: u32,
-        // The type given to the function
-        actual: wgt::BindingType,
-        // Human-readable description of expected types
+        // Human-readable description of the resource given to the function
+        actual: &'static str,
+        // Human-readable description of the type declared in the layout
         expected: &'static str,
     },
-    #[error("the given sampler is/is not a comparison sampler, while the layout type indicates otherwise")]
-    WrongSamplerComparison,
+    #[error("the sampler at binding {binding} is/is not a comparison sampler, while the layout type indicates otherwise")]
+    WrongSamplerComparison { binding: u32 },
     #[error("uniform buffer binding range exceeds `max_uniform_buffer_binding_size` limit")]
     UniformBufferRangeTooLarge,
+    #[error("texture is bound as both sampled and storage, but its format {format:?} can't be used for storage")]
+    SampledStorageFormatConflict { format: wgt::TextureFormat },
+    #[error("binding {binding} is given {actual} texture views, but the layout declares an array of {expected}")]
+    WrongArrayLength {
+        binding: u32,
+        actual: usize,
+        expected: usize,
+    },
+    #[error("buffer binding {binding} is {provided} bytes long, which is less than the minimum of {minimum} declared in the layout")]
+    BindingSizeTooSmall {
+        binding: u32,
+        provided: wgt::BufferAddress,
+        minimum: wgt::BufferAddress,
+    },
+    #[error("buffer binding {binding} is invalid: {error}")]
+    InvalidBufferBinding { binding: u32, error: BindError },
+    #[error("binding {binding} references a resource that has been destroyed")]
+    DestroyedResource { binding: u32 },
+    #[error("bind group provides dynamic buffers at bindings {actual:?}, but the layout declares them at {expected:?}")]
+    DynamicBindingMismatch {
+        expected: Vec<u32>,
+        actual: Vec<u32>,
+    },
+    #[error("texture view at binding {binding} has dimension {actual:?}, but the layout expects {expected:?}")]
+    WrongViewDimension {
+        binding: u32,
+        actual: wgt::TextureViewDimension,
+        expected: wgt::TextureViewDimension,
+    },
+    #[error("cube texture view at binding {binding} has {layers} layers, which don't make up whole cubes")]
+    IncompleteCubeView { binding: u32, layers: u32 },
+    #[error("texture bound at binding {binding} can't be used there: {error}")]
+    MissingTextureUsage {
+        binding: u32,
+        error: MissingTextureUsageError,
+    },
+}
+
+/// Returns true if textures of this format can be bound as storage textures.
+pub(crate) fn is_storage_format(format: wgt::TextureFormat) -> bool {
+    use wgt::TextureFormat as Tf;
+    match format {
+        Tf::R32Uint
+        | Tf::R32Sint
+        | Tf::R32Float
+        | Tf::Rg32Uint
+        | Tf::Rg32Sint
+        | Tf::Rg32Float
+        | Tf::Rgba8Unorm
+        | Tf::Rgba8Snorm
+        | Tf::Rgba8Uint
+        | Tf::Rgba8Sint
+        | Tf::Rgba16Uint
+        | Tf::Rgba16Sint
+        | Tf::Rgba16Float
+        | Tf::Rgba32Uint
+        | Tf::Rgba32Sint
+        | Tf::Rgba32Float => true,
+        _ => false,
+    }
+}
+
+/// Tracks the roles each texture is bound in across the entries of a bind group.
+#[derive(Debug, Default)]
+pub(crate) struct TextureRoleValidator {
+    textures: FastHashMap<TextureId, TextureRoles>,
+}
+
+#[derive(Debug)]
+struct TextureRoles {
+    format: wgt::TextureFormat,
+    sampled: bool,
+    storage: bool,
+}
+
+impl TextureRoleValidator {
+    pub(crate) fn add_view(
+        &mut self,
+        texture: TextureId,
+        format: wgt::TextureFormat,
+        storage: bool,
+    ) {
+        let roles = self.textures.entry(texture).or_insert(TextureRoles {
+            format,
+            sampled: false,
+            storage: false,
+        });
+        if storage {
+            roles.storage = true;
+        } else {
+            roles.sampled = true;
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), CreateBindGroupError> {
+        for roles in self.textures.values() {
+            if roles.sampled && roles.storage && !is_storage_format(roles.format) {
+                return Err(CreateBindGroupError::SampledStorageFormatConflict {
+                    format: roles.format,
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, Error)]
@@ -163,7 +371,9 @@ impl BindingTypeMaxCountValidator {
             wgt::BindingType::SampledTexture { .. } => {
                 self.sampled_textures.add(binding.visibility, count);
             }
-            wgt::BindingType::StorageTexture { .. } => {
+            // Texel buffers take up storage image slots on the backends.
+            wgt::BindingType::StorageTexture { .. }
+            | wgt::BindingType::StorageTexelBuffer { .. } => {
                 self.storage_textures.add(binding.visibility, count);
             }
         }
@@ -222,7 +432,8 @@ impl BindingTypeMaxCountValidator {
     }
 }
 
-pub(crate) type BindEntryMap = FastHashMap<u32, wgt::BindGroupLayoutEntry>;
+/// Entries of a bind group layout, by binding index.
+pub type BindEntryMap = FastHashMap<u32, wgt::BindGroupLayoutEntry>;
 
 #[derive(Debug)]
 pub struct BindGroupLayout<B: hal::Backend> {
@@ -233,6 +444,116 @@ pub struct BindGroupLayout<B: hal::Backend> {
     pub(crate) desc_counts: DescriptorCounts,
     pub(crate) dynamic_count: usize,
     pub(crate) count_validator: BindingTypeMaxCountValidator,
+    /// Key of the entries, which the device looks up identical layouts by.
+    pub(crate) key: BindGroupLayoutKey,
+    /// Fingerprint of the key, copied into every bind group created with this layout.
+    pub(crate) fingerprint: u64,
+}
+
+impl<B: hal::Backend> BindGroupLayout<B> {
+    /// Returns a key that compares and hashes equal for layouts with the same entries.
+    pub fn key(&self) -> &BindGroupLayoutKey {
+        &self.key
+    }
+}
+
+/// Structural identity of a bind group layout, independent of the order its
+/// entries were declared in.
+///
+/// The descriptor counts and the dynamic binding count are derived from the
+/// entries, so two layouts with equal keys are interchangeable.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BindGroupLayoutKey {
+    entries: Vec<wgt::BindGroupLayoutEntry>,
+}
+
+impl BindGroupLayoutKey {
+    pub(crate) fn new(entries: &BindEntryMap) -> Self {
+        let mut entries = entries.values().cloned().collect::<Vec<_>>();
+        entries.sort_by_key(|entry| entry.binding);
+        Self { entries }
+    }
+
+    /// Returns a hash of the key, which is cheap to store and compare.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = fxhash::FxHasher::default();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+fn map_raw_binding(entry: &wgt::BindGroupLayoutEntry) -> hal::pso::DescriptorSetLayoutBinding {
+    hal::pso::DescriptorSetLayoutBinding {
+        binding: entry.binding,
+        ty: conv::map_binding_type(entry),
+        count: entry
+            .count
+            .map_or(1, |v| v as hal::pso::DescriptorArrayIndex), //TODO: consolidate
+        stage_flags: conv::map_shader_stage_flags(entry.visibility),
+        immutable_samplers: false, // TODO
+    }
+}
+
+/// Returns how many descriptors of each type a bind group with these layout
+/// entries needs, counting every element of arrayed entries.
+///
+/// This allows estimating the descriptor pool usage before the layout is created.
+pub fn descriptor_counts(entries: &BindEntryMap) -> DescriptorCounts {
+    entries.values().map(map_raw_binding).collect()
+}
+
+/// Collects bind group layout entries one by one, rejecting duplicate binding
+/// numbers as soon as they are added.
+///
+/// Besides the entry map used by `check_stage`, the builder keeps track of the
+/// raw descriptor bindings and the number of dynamic bindings of the layout.
+#[derive(Debug, Default)]
+pub struct BindGroupLayoutBuilder {
+    entries: BindEntryMap,
+    raw_bindings: Vec<hal::pso::DescriptorSetLayoutBinding>,
+    dynamic_count: usize,
+}
+
+impl BindGroupLayoutBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entry(mut self, entry: wgt::BindGroupLayoutEntry) -> Result<Self, BindGroupLayoutError> {
+        if let Some(existing) = self.entries.get(&entry.binding) {
+            return Err(BindGroupLayoutError::ConflictBinding {
+                binding: entry.binding,
+                existing: existing.ty.clone(),
+                incoming: entry.ty,
+            });
+        }
+        self.raw_bindings.push(map_raw_binding(&entry));
+        if entry.has_dynamic_offset() {
+            self.dynamic_count += 1;
+        }
+        self.entries.insert(entry.binding, entry);
+        Ok(self)
+    }
+
+    pub fn entries(&self) -> &BindEntryMap {
+        &self.entries
+    }
+
+    pub fn dynamic_count(&self) -> usize {
+        self.dynamic_count
+    }
+
+    pub fn desc_counts(&self) -> DescriptorCounts {
+        descriptor_counts(&self.entries)
+    }
+
+    pub(crate) fn raw_bindings(&self) -> &[hal::pso::DescriptorSetLayoutBinding] {
+        &self.raw_bindings
+    }
+
+    pub fn finish(self) -> BindEntryMap {
+        self.entries
+    }
 }
 
 #[derive(Clone, Debug, Error)]
@@ -260,6 +581,87 @@ pub enum PipelineLayoutError {
     MisalignedPushConstantRange { index: usize, bound: u32 },
     #[error("device does not have required feature: {0:?}")]
     MissingFeature(wgt::Features),
+    #[error(
+        "push constant range count {0} exceeds the maximum of {}",
+        MAX_PUSH_CONSTANT_RANGES
+    )]
+    TooManyPushConstantRanges(usize),
+    #[error(
+        "shader uses bind group {set}, but the pipeline layout only has {count} bind group(s)"
+    )]
+    MissingBindGroup { set: u32, count: usize },
+    #[error(
+        "shader stage {stage:?} uses push constants, but no push constant range is visible to it"
+    )]
+    MissingPushConstants { stage: wgt::ShaderStage },
+}
+
+/// Checks the number of bind group layouts of a pipeline layout descriptor.
+///
+/// The limit is capped by `MAX_BIND_GROUPS`, since that's all the pipeline
+/// layout has room for, whatever the device reports.
+pub(crate) fn check_bind_group_count(
+    count: usize,
+    limits: &wgt::Limits,
+) -> Result<(), PipelineLayoutError> {
+    let max = (limits.max_bind_groups as usize).min(MAX_BIND_GROUPS);
+    if count > max {
+        return Err(PipelineLayoutError::TooManyGroups { actual: count, max });
+    }
+    Ok(())
+}
+
+/// Checks the push constant ranges of a pipeline layout descriptor against
+/// the device features and limits.
+pub(crate) fn check_push_constant_ranges(
+    ranges: &[wgt::PushConstantRange],
+    features: wgt::Features,
+    limits: &wgt::Limits,
+) -> Result<(), PipelineLayoutError> {
+    if ranges.len() > MAX_PUSH_CONSTANT_RANGES {
+        return Err(PipelineLayoutError::TooManyPushConstantRanges(ranges.len()));
+    }
+
+    if !ranges.is_empty() && !features.contains(wgt::Features::PUSH_CONSTANTS) {
+        return Err(PipelineLayoutError::MissingFeature(
+            wgt::Features::PUSH_CONSTANTS,
+        ));
+    }
+    let mut used_stages = wgt::ShaderStage::empty();
+    for (index, pc) in ranges.iter().enumerate() {
+        if pc.stages.intersects(used_stages) {
+            return Err(PipelineLayoutError::MoreThanOnePushConstantRangePerStage {
+                index,
+                provided: pc.stages,
+                intersected: pc.stages & used_stages,
+            });
+        }
+        used_stages |= pc.stages;
+
+        let device_max_pc_size = limits.max_push_constant_size;
+        if device_max_pc_size < pc.range.end {
+            return Err(PipelineLayoutError::PushConstantRangeTooLarge {
+                index,
+                range: pc.range.clone(),
+                max: device_max_pc_size,
+            });
+        }
+
+        if pc.range.start % wgt::PUSH_CONSTANT_ALIGNMENT != 0 {
+            return Err(PipelineLayoutError::MisalignedPushConstantRange {
+                index,
+                bound: pc.range.start,
+            });
+        }
+        if pc.range.end % wgt::PUSH_CONSTANT_ALIGNMENT != 0 {
+            return Err(PipelineLayoutError::MisalignedPushConstantRange {
+                index,
+                bound: pc.range.end,
+            });
+        }
+    }
+
+    Ok(())
 }
 
 #[derive(Clone, Debug, Error)]
@@ -301,7 +703,9 @@ pub struct PipelineLayout<B: hal::Backend> {
     pub(crate) device_id: Stored<DeviceId>,
     pub(crate) life_guard: LifeGuard,
     pub(crate) bind_group_layout_ids: ArrayVec<[Stored<BindGroupLayoutId>; MAX_BIND_GROUPS]>,
-    pub(crate) push_constant_ranges: ArrayVec<[wgt::PushConstantRange; SHADER_STAGE_COUNT]>,
+    /// Fingerprints of the bind group layouts, recorded while they were alive.
+    pub(crate) bind_group_layout_fingerprints: ArrayVec<[u64; MAX_BIND_GROUPS]>,
+    pub(crate) push_constant_ranges: ArrayVec<[wgt::PushConstantRange; MAX_PUSH_CONSTANT_RANGES]>,
 }
 
 impl<B: hal::Backend> PipelineLayout<B> {
@@ -404,28 +808,419 @@ pub enum BindingResource<'a> {
     TextureViewArray(&'a [TextureViewId]),
 }
 
+/// Checks that single resources are bound to non-arrayed layout entries,
+/// and resource arrays are bound to arrayed ones of the same length.
+pub(crate) fn check_resource_arrayness(
+    binding: u32,
+    resource: &BindingResource,
+    decl: &wgt::BindGroupLayoutEntry,
+) -> Result<(), CreateBindGroupError> {
+    match (resource, decl.count) {
+        (BindingResource::TextureViewArray(_), None) => {
+            Err(CreateBindGroupError::WrongBindingType {
+                binding,
+                actual: describe_binding_resource(resource),
+                expected: "arrayed SampledTexture",
+            })
+        }
+        (BindingResource::TextureView(_), Some(_)) => Err(CreateBindGroupError::WrongBindingType {
+            binding,
+            actual: describe_binding_resource(resource),
+            expected:
+                "non-arrayed SampledTexture, ReadonlyStorageTexture or WriteonlyStorageTexture",
+        }),
+        (BindingResource::TextureViewArray(views), Some(count))
+            if views.len() != count as usize =>
+        {
+            Err(CreateBindGroupError::WrongArrayLength {
+                binding,
+                actual: views.len(),
+                expected: count as usize,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that the resource behind `binding` hasn't been dropped by the user.
+pub(crate) fn check_resource_alive(
+    binding: u32,
+    life_guard: &LifeGuard,
+) -> Result<(), CreateBindGroupError> {
+    if life_guard.ref_count.is_some() {
+        Ok(())
+    } else {
+        Err(CreateBindGroupError::DestroyedResource { binding })
+    }
+}
+
+/// Returns the alignment the dynamic offsets of a buffer binding of type `ty`
+/// must have, or `None` if the binding doesn't take a dynamic offset.
+pub(crate) fn dynamic_offset_alignment(
+    ty: &wgt::BindingType,
+    limits: &hal::Limits,
+) -> Option<wgt::BufferAddress> {
+    match *ty {
+        wgt::BindingType::UniformBuffer { dynamic: true, .. } => {
+            Some(limits.min_uniform_buffer_offset_alignment)
+        }
+        wgt::BindingType::StorageBuffer { dynamic: true, .. } => {
+            Some(limits.min_storage_buffer_offset_alignment)
+        }
+        _ => None,
+    }
+}
+
+/// Checks that the dynamic binding info of a bind group was recorded for
+/// exactly the dynamic entries of its layout, in binding order, so that each
+/// dynamic offset ends up applied to the right buffer.
+pub(crate) fn check_dynamic_bindings(
+    layout_entries: &BindEntryMap,
+    infos: &[BindGroupDynamicBindingData],
+) -> Result<(), CreateBindGroupError> {
+    let mut expected = layout_entries
+        .values()
+        .filter(|entry| match entry.ty {
+            wgt::BindingType::UniformBuffer { dynamic, .. }
+            | wgt::BindingType::StorageBuffer { dynamic, .. } => dynamic,
+            _ => false,
+        })
+        .map(|entry| entry.binding)
+        .collect::<Vec<_>>();
+    expected.sort_unstable();
+    let actual = infos.iter().map(|info| info.binding).collect::<Vec<_>>();
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(CreateBindGroupError::DynamicBindingMismatch { expected, actual })
+    }
+}
+
+/// Checks that a view bound to a cube or cube array entry has exactly six
+/// layers, or a positive multiple of six for arrays.
+pub(crate) fn check_cube_view_layers(
+    binding: u32,
+    ty: &wgt::BindingType,
+    layers: u32,
+) -> Result<(), CreateBindGroupError> {
+    let dimension = match *ty {
+        wgt::BindingType::SampledTexture { dimension, .. }
+        | wgt::BindingType::StorageTexture { dimension, .. } => dimension,
+        _ => return Ok(()),
+    };
+    let complete = match dimension {
+        wgt::TextureViewDimension::Cube => layers == 6,
+        wgt::TextureViewDimension::CubeArray => layers != 0 && layers % 6 == 0,
+        _ => true,
+    };
+    if complete {
+        Ok(())
+    } else {
+        Err(CreateBindGroupError::IncompleteCubeView { binding, layers })
+    }
+}
+
+/// Checks that the effective size of a buffer binding is at least the
+/// `min_binding_size` of its layout entry.
+pub(crate) fn check_binding_size(
+    binding: u32,
+    provided: wgt::BufferAddress,
+    min_binding_size: Option<wgt::BufferSize>,
+) -> Result<(), CreateBindGroupError> {
+    match min_binding_size {
+        Some(minimum) if minimum.get() > provided => {
+            Err(CreateBindGroupError::BindingSizeTooSmall {
+                binding,
+                provided,
+                minimum: minimum.get(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that a texture view bound at `binding` has the dimension the layout
+/// entry declares.
+pub(crate) fn check_view_dimension(
+    binding: u32,
+    ty: &wgt::BindingType,
+    actual: wgt::TextureViewDimension,
+) -> Result<(), CreateBindGroupError> {
+    match *ty {
+        wgt::BindingType::SampledTexture {
+            dimension: expected,
+            ..
+        }
+        | wgt::BindingType::StorageTexture {
+            dimension: expected,
+            ..
+        } if expected != actual => Err(CreateBindGroupError::WrongViewDimension {
+            binding,
+            actual,
+            expected,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Checks that a texture bound at `binding` was created with the `expected` usage.
+pub(crate) fn check_bound_texture_usage(
+    binding: u32,
+    actual: wgt::TextureUsage,
+    expected: wgt::TextureUsage,
+) -> Result<(), CreateBindGroupError> {
+    check_texture_usage(actual, expected)
+        .map_err(|error| CreateBindGroupError::MissingTextureUsage { binding, error })
+}
+
 pub type BindGroupEntry<'a> = wgt::BindGroupEntry<BindingResource<'a>>;
 
 pub type BindGroupDescriptor<'a> =
     wgt::BindGroupDescriptor<'a, BindGroupLayoutId, BindGroupEntry<'a>>;
 
+/// Checks that a bound sampler is a comparison sampler exactly when the
+/// layout entry declares one.
+pub(crate) fn check_sampler_comparison(
+    binding: u32,
+    comparison: bool,
+    ty: &wgt::BindingType,
+) -> Result<(), CreateBindGroupError> {
+    match *ty {
+        wgt::BindingType::Sampler {
+            comparison: expected,
+        } if expected != comparison => {
+            Err(CreateBindGroupError::WrongSamplerComparison { binding })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns a human-readable description of a bound resource, used in error
+/// messages.
+pub fn describe_binding_resource(resource: &BindingResource) -> &'static str {
+    match *resource {
+        BindingResource::Buffer(_) => "buffer",
+        BindingResource::Sampler(_) => "sampler",
+        BindingResource::TextureView(_) => "texture view",
+        BindingResource::TextureViewArray(_) => "texture view array",
+    }
+}
+
+/// Returns a stable human-readable description of a binding type, used in
+/// error messages.
+pub fn describe_binding_type(ty: &wgt::BindingType) -> &'static str {
+    use wgt::{BindingType as Bt, TextureViewDimension as Tvd};
+    match *ty {
+        Bt::UniformBuffer { dynamic: false, .. } => "uniform buffer",
+        Bt::UniformBuffer { dynamic: true, .. } => "dynamic uniform buffer",
+        Bt::StorageBuffer {
+            dynamic: false,
+            readonly: false,
+            ..
+        } => "storage buffer",
+        Bt::StorageBuffer {
+            dynamic: false,
+            readonly: true,
+            ..
+        } => "read-only storage buffer",
+        Bt::StorageBuffer {
+            dynamic: true,
+            readonly: false,
+            ..
+        } => "dynamic storage buffer",
+        Bt::StorageBuffer {
+            dynamic: true,
+            readonly: true,
+            ..
+        } => "dynamic read-only storage buffer",
+        Bt::Sampler { comparison: false } => "sampler",
+        Bt::Sampler { comparison: true } => "comparison sampler",
+        Bt::SampledTexture {
+            multisampled: true, ..
+        } => "multisampled texture",
+        Bt::SampledTexture { dimension, .. } => match dimension {
+            Tvd::D1 => "sampled 1D texture",
+            Tvd::D2 => "sampled 2D texture",
+            Tvd::D2Array => "sampled 2D array texture",
+            Tvd::Cube => "sampled cube texture",
+            Tvd::CubeArray => "sampled cube array texture",
+            Tvd::D3 => "sampled 3D texture",
+        },
+        Bt::StorageTexture { readonly: true, .. } => "read-only storage texture",
+        Bt::StorageTexture {
+            readonly: false, ..
+        } => "writable storage texture",
+        Bt::StorageTexelBuffer { readonly: true, .. } => "read-only texel buffer",
+        Bt::StorageTexelBuffer {
+            readonly: false, ..
+        } => "writable texel buffer",
+    }
+}
+
+/// Checks that the kind of the bound resource fits the layout entry.
+fn check_resource_type(
+    binding: u32,
+    resource: &BindingResource,
+    decl: &wgt::BindGroupLayoutEntry,
+) -> Result<(), CreateBindGroupError> {
+    match (resource, &decl.ty) {
+        (BindingResource::Buffer(_), wgt::BindingType::UniformBuffer { .. })
+        | (BindingResource::Buffer(_), wgt::BindingType::StorageBuffer { .. })
+        | (BindingResource::Buffer(_), wgt::BindingType::StorageTexelBuffer { .. })
+        | (BindingResource::Sampler(_), wgt::BindingType::Sampler { .. })
+        | (BindingResource::TextureView(_), wgt::BindingType::SampledTexture { .. })
+        | (BindingResource::TextureView(_), wgt::BindingType::StorageTexture { .. })
+        | (BindingResource::TextureViewArray(_), wgt::BindingType::SampledTexture { .. }) => Ok(()),
+        _ => Err(CreateBindGroupError::WrongBindingType {
+            binding,
+            actual: describe_binding_resource(resource),
+            expected: describe_binding_type(&decl.ty),
+        }),
+    }
+}
+
+/// Checks the entries of a bind group descriptor against its layout:
+/// every layout entry has to be bound exactly once, by a resource of the
+/// declared kind and arrayness.
+///
+/// This doesn't look at the resources themselves, so it can run before any
+/// of them are locked.
+pub fn validate_bind_group(
+    desc: &BindGroupDescriptor,
+    layout: &BindEntryMap,
+) -> Result<(), CreateBindGroupError> {
+    let actual = desc.entries.len();
+    let expected = layout.len();
+    if actual != expected {
+        return Err(CreateBindGroupError::BindingsNumMismatch { expected, actual });
+    }
+    let mut seen = fxhash::FxHashSet::default();
+    for entry in desc.entries {
+        if !seen.insert(entry.binding) {
+            return Err(CreateBindGroupError::DuplicateBinding(entry.binding));
+        }
+        let decl =
+            layout
+                .get(&entry.binding)
+                .ok_or(CreateBindGroupError::MissingBindingDeclaration(
+                    entry.binding,
+                ))?;
+        check_resource_type(entry.binding, &entry.resource, decl)?;
+        check_resource_arrayness(entry.binding, &entry.resource, decl)?;
+    }
+    Ok(())
+}
+
 #[derive(Clone, Debug, Error)]
 pub enum BindError {
     #[error("number of dynamic offsets ({actual}) doesn't match the number of dynamic bindings in the bind group layout ({expected})")]
     MismatchedDynamicOffsetCount { actual: usize, expected: usize },
-    #[error(
-        "dynamic binding at index {idx}: offset {offset} must be aligned to {}",
-        wgt::BIND_BUFFER_ALIGNMENT
-    )]
-    UnalignedDynamicBinding { idx: usize, offset: u32 },
-    #[error("dynamic binding at index {idx} with offset {offset} would overrun the buffer (limit: {max})")]
+    #[error("dynamic binding at index {idx}: offset {offset} must be aligned to {alignment}")]
+    UnalignedDynamicBinding {
+        idx: usize,
+        offset: u32,
+        alignment: wgt::BufferAddress,
+    },
+    #[error("dynamic binding at index {idx} with offset {offset} would overrun the buffer (maximum allowed offset: {max})")]
     DynamicBindingOutOfBounds { idx: usize, offset: u32, max: u64 },
+    #[error("the bind group layout of the bind group changed since the group was created")]
+    StaleLayout,
+    #[error("binding {binding} of bind group {set} is used by the pipeline, but not provided by the bound groups")]
+    MissingBoundBinding { set: u32, binding: u32 },
+    #[error("buffer offset {offset} must be aligned to {alignment}")]
+    UnalignedBufferOffset {
+        offset: wgt::BufferAddress,
+        alignment: wgt::BufferAddress,
+    },
+    #[error("buffer range of {size} bytes at offset {offset} doesn't fit in the buffer of {buffer_size} bytes")]
+    BufferRangeOutOfBounds {
+        offset: wgt::BufferAddress,
+        size: wgt::BufferAddress,
+        buffer_size: wgt::BufferAddress,
+    },
+    #[error("buffer offset {offset} leaves no bytes to bind")]
+    EmptyBufferRange { offset: wgt::BufferAddress },
+}
+
+/// Checks that a buffer binding is aligned and fits within the buffer.
+///
+/// A binding without a size covers the rest of the buffer past its offset,
+/// which must not be empty.
+pub fn validate_buffer_binding(
+    binding: &BufferBinding,
+    buffer_size: wgt::BufferAddress,
+    alignment: wgt::BufferAddress,
+) -> Result<(), BindError> {
+    if binding.offset % alignment != 0 {
+        return Err(BindError::UnalignedBufferOffset {
+            offset: binding.offset,
+            alignment,
+        });
+    }
+    match binding.size {
+        Some(size) => {
+            let end = binding.offset.checked_add(size.get());
+            if end.map_or(true, |end| end > buffer_size) {
+                return Err(BindError::BufferRangeOutOfBounds {
+                    offset: binding.offset,
+                    size: size.get(),
+                    buffer_size,
+                });
+            }
+        }
+        None if binding.offset > buffer_size => {
+            return Err(BindError::BufferRangeOutOfBounds {
+                offset: binding.offset,
+                size: 0,
+                buffer_size,
+            })
+        }
+        None if binding.offset == buffer_size => {
+            return Err(BindError::EmptyBufferRange {
+                offset: binding.offset,
+            })
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+/// Checks that every `(set, binding)` pair used by the stages of a pipeline
+/// is provided by the layout of the bind group bound at that set.
+pub fn validate_bound_coverage(
+    pipeline_stage_bindings: &[(u32, u32)],
+    bound_groups: &[Option<&BindEntryMap>],
+) -> Result<(), BindError> {
+    for &(set, binding) in pipeline_stage_bindings {
+        let provided = bound_groups
+            .get(set as usize)
+            .and_then(|group| *group)
+            .map_or(false, |entries| entries.contains_key(&binding));
+        if !provided {
+            return Err(BindError::MissingBoundBinding { set, binding });
+        }
+    }
+    Ok(())
+}
+
+/// Checks the layout fingerprint recorded by a bind group against the one a
+/// pipeline layout recorded for the same layout ID.
+fn check_layout_fingerprint(recorded: u64, expected: u64) -> Result<(), BindError> {
+    if recorded == expected {
+        Ok(())
+    } else {
+        Err(BindError::StaleLayout)
+    }
 }
 
 #[derive(Debug)]
 pub struct BindGroupDynamicBindingData {
+    /// The binding number of the buffer within the bind group.
+    pub(crate) binding: u32,
     /// The maximum value the dynamic offset can have before running off the end of the buffer.
     pub(crate) maximum_dynamic_offset: wgt::BufferAddress,
+    /// The alignment the dynamic offset must have, which depends on the type of the buffer binding.
+    pub(crate) required_alignment: wgt::BufferAddress,
 }
 
 #[derive(Debug)]
@@ -433,43 +1228,99 @@ pub struct BindGroup<B: hal::Backend> {
     pub(crate) raw: DescriptorSet<B>,
     pub(crate) device_id: Stored<DeviceId>,
     pub(crate) layout_id: BindGroupLayoutId,
+    pub(crate) layout_fingerprint: u64,
     pub(crate) life_guard: LifeGuard,
     pub(crate) used: TrackerSet,
     pub(crate) dynamic_binding_info: Vec<BindGroupDynamicBindingData>,
+    /// Views of the buffers bound as texel buffers.
+    pub(crate) buffer_views: Vec<B::BufferView>,
+}
+
+/// Lists the problems with each of the dynamic `offsets`, in binding order.
+fn dynamic_offset_errors<'a>(
+    infos: &'a [BindGroupDynamicBindingData],
+    offsets: &'a [wgt::DynamicOffset],
+) -> impl Iterator<Item = BindError> + 'a {
+    infos
+        .iter()
+        .zip(offsets.iter())
+        .enumerate()
+        .flat_map(|(idx, (info, &offset))| {
+            let unaligned = if offset as wgt::BufferAddress % info.required_alignment != 0 {
+                Some(BindError::UnalignedDynamicBinding {
+                    idx,
+                    offset,
+                    alignment: info.required_alignment,
+                })
+            } else {
+                None
+            };
+            let out_of_bounds = if offset as wgt::BufferAddress > info.maximum_dynamic_offset {
+                Some(BindError::DynamicBindingOutOfBounds {
+                    idx,
+                    offset,
+                    max: info.maximum_dynamic_offset,
+                })
+            } else {
+                None
+            };
+            unaligned.into_iter().chain(out_of_bounds)
+        })
 }
 
 impl<B: hal::Backend> BindGroup<B> {
-    pub(crate) fn validate_dynamic_bindings(
+    /// Checks that the layout this group was created with is the one `pipeline_layout`
+    /// was created with at `index`, when both refer to the same layout ID.
+    pub(crate) fn validate_layout(
         &self,
-        offsets: &[wgt::DynamicOffset],
+        pipeline_layout: &PipelineLayout<B>,
+        index: usize,
     ) -> Result<(), BindError> {
+        match pipeline_layout.bind_group_layout_ids.get(index) {
+            Some(id) if id.value == self.layout_id => check_layout_fingerprint(
+                self.layout_fingerprint,
+                pipeline_layout.bind_group_layout_fingerprints[index],
+            ),
+            // Groups of other layouts are left incompatible by the binder.
+            _ => Ok(()),
+        }
+    }
+
+    fn check_dynamic_offset_count(&self, offsets: &[wgt::DynamicOffset]) -> Result<(), BindError> {
         if self.dynamic_binding_info.len() != offsets.len() {
             return Err(BindError::MismatchedDynamicOffsetCount {
                 expected: self.dynamic_binding_info.len(),
                 actual: offsets.len(),
             });
         }
+        Ok(())
+    }
 
-        for (idx, (info, &offset)) in self
-            .dynamic_binding_info
-            .iter()
-            .zip(offsets.iter())
-            .enumerate()
-        {
-            if offset as wgt::BufferAddress % wgt::BIND_BUFFER_ALIGNMENT != 0 {
-                return Err(BindError::UnalignedDynamicBinding { idx, offset });
-            }
-
-            if offset as wgt::BufferAddress > info.maximum_dynamic_offset {
-                return Err(BindError::DynamicBindingOutOfBounds {
-                    idx,
-                    offset,
-                    max: info.maximum_dynamic_offset,
-                });
-            }
+    pub(crate) fn validate_dynamic_bindings(
+        &self,
+        offsets: &[wgt::DynamicOffset],
+    ) -> Result<(), BindError> {
+        self.check_dynamic_offset_count(offsets)?;
+        match dynamic_offset_errors(&self.dynamic_binding_info, offsets).next() {
+            Some(error) => Err(error),
+            None => Ok(()),
         }
+    }
 
-        Ok(())
+    /// Like `validate_dynamic_bindings`, but reports every problem with the
+    /// given offsets instead of stopping at the first one.
+    pub fn validate_dynamic_bindings_all(
+        &self,
+        offsets: &[wgt::DynamicOffset],
+    ) -> Result<(), Vec<BindError>> {
+        self.check_dynamic_offset_count(offsets)
+            .map_err(|error| vec![error])?;
+        let errors = dynamic_offset_errors(&self.dynamic_binding_info, offsets).collect::<Vec<_>>();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
     }
 }
 
@@ -484,3 +1335,1043 @@ impl<B: hal::Backend> Borrow<()> for BindGroup<B> {
         &DUMMY_SELECTOR
     }
 }
+
+#[test]
+fn test_destroyed_resource() {
+    let mut life_guard = LifeGuard::new();
+    assert!(check_resource_alive(0, &life_guard).is_ok());
+    // This is what `buffer_destroy` does to the buffer's guard.
+    drop(life_guard.ref_count.take());
+    match check_resource_alive(4, &life_guard) {
+        Err(CreateBindGroupError::DestroyedResource { binding: 4 }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_too_many_push_constant_ranges() {
+    let limits = wgt::Limits {
+        max_push_constant_size: 128,
+        ..wgt::Limits::default()
+    };
+    let ranges = (0..MAX_PUSH_CONSTANT_RANGES + 1)
+        .map(|_| wgt::PushConstantRange {
+            stages: wgt::ShaderStage::NONE,
+            range: 0..4,
+        })
+        .collect::<Vec<_>>();
+    match check_push_constant_ranges(&ranges, wgt::Features::PUSH_CONSTANTS, &limits) {
+        Err(PipelineLayoutError::TooManyPushConstantRanges(count)) => {
+            assert_eq!(count, MAX_PUSH_CONSTANT_RANGES + 1)
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_overlapping_push_constant_ranges() {
+    let limits = wgt::Limits {
+        max_push_constant_size: 128,
+        ..wgt::Limits::default()
+    };
+    let ranges = [
+        wgt::PushConstantRange {
+            stages: wgt::ShaderStage::VERTEX | wgt::ShaderStage::FRAGMENT,
+            range: 0..16,
+        },
+        wgt::PushConstantRange {
+            stages: wgt::ShaderStage::FRAGMENT,
+            range: 16..32,
+        },
+    ];
+    match check_push_constant_ranges(&ranges, wgt::Features::PUSH_CONSTANTS, &limits) {
+        Err(PipelineLayoutError::MoreThanOnePushConstantRangePerStage {
+            index: 1,
+            provided: wgt::ShaderStage::FRAGMENT,
+            intersected: wgt::ShaderStage::FRAGMENT,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_push_constant_range_over_limit() {
+    let limits = wgt::Limits {
+        max_push_constant_size: 128,
+        ..wgt::Limits::default()
+    };
+    let ranges = [wgt::PushConstantRange {
+        stages: wgt::ShaderStage::COMPUTE,
+        range: 64..132,
+    }];
+    match check_push_constant_ranges(&ranges, wgt::Features::PUSH_CONSTANTS, &limits) {
+        Err(PipelineLayoutError::PushConstantRangeTooLarge {
+            index: 0, max: 128, ..
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_push_constants_require_feature() {
+    let limits = wgt::Limits {
+        max_push_constant_size: 128,
+        ..wgt::Limits::default()
+    };
+    let ranges = [wgt::PushConstantRange {
+        stages: wgt::ShaderStage::VERTEX,
+        range: 0..16,
+    }];
+    match check_push_constant_ranges(&ranges, wgt::Features::empty(), &limits) {
+        Err(PipelineLayoutError::MissingFeature(wgt::Features::PUSH_CONSTANTS)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(check_push_constant_ranges(&[], wgt::Features::empty(), &limits).is_ok());
+    assert!(check_push_constant_ranges(&ranges, wgt::Features::PUSH_CONSTANTS, &limits).is_ok());
+}
+
+#[test]
+fn test_binding_array_count_over_limit() {
+    let limits = wgt::Limits::default();
+    let entry = wgt::BindGroupLayoutEntry {
+        count: Some(limits.max_sampled_textures_per_shader_stage + 1),
+        ..wgt::BindGroupLayoutEntry::new(
+            2,
+            wgt::ShaderStage::FRAGMENT,
+            wgt::BindingType::SampledTexture {
+                dimension: wgt::TextureViewDimension::D2,
+                component_type: wgt::TextureComponentType::Float,
+                multisampled: false,
+            },
+        )
+    };
+    let features = wgt::Features::SAMPLED_TEXTURE_BINDING_ARRAY;
+    match check_binding_count(&entry, features, &limits) {
+        Err(BindGroupLayoutError::ArrayCountExceedsLimit {
+            binding: 2,
+            count,
+            limit,
+        }) => {
+            assert_eq!(count, limit + 1);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_arrayed_binding_types() {
+    let limits = wgt::Limits::default();
+    let sampler = wgt::BindGroupLayoutEntry {
+        count: Some(2),
+        ..wgt::BindGroupLayoutEntry::new(
+            0,
+            wgt::ShaderStage::FRAGMENT,
+            wgt::BindingType::Sampler { comparison: false },
+        )
+    };
+    match check_binding_count(&sampler, wgt::Features::all(), &limits) {
+        Err(BindGroupLayoutError::ArrayUnsupported) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let texture = wgt::BindGroupLayoutEntry {
+        count: Some(2),
+        ..wgt::BindGroupLayoutEntry::new(
+            1,
+            wgt::ShaderStage::FRAGMENT,
+            wgt::BindingType::SampledTexture {
+                dimension: wgt::TextureViewDimension::D2,
+                component_type: wgt::TextureComponentType::Float,
+                multisampled: false,
+            },
+        )
+    };
+    match check_binding_count(&texture, wgt::Features::empty(), &limits) {
+        Err(BindGroupLayoutError::MissingFeature(wgt::Features::SAMPLED_TEXTURE_BINDING_ARRAY)) => {
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+    let features = wgt::Features::SAMPLED_TEXTURE_BINDING_ARRAY;
+    assert!(check_binding_count(&texture, features, &limits).is_ok());
+}
+
+#[test]
+fn test_dynamic_binding_array() {
+    let entry = wgt::BindGroupLayoutEntry {
+        count: Some(4),
+        ..wgt::BindGroupLayoutEntry::new(
+            1,
+            wgt::ShaderStage::VERTEX,
+            wgt::BindingType::UniformBuffer {
+                dynamic: true,
+                min_binding_size: None,
+            },
+        )
+    };
+    match check_binding_count(&entry, wgt::Features::all(), &wgt::Limits::default()) {
+        Err(BindGroupLayoutError::DynamicArrayUnsupported(1)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_resource_arrayness() {
+    let ty = wgt::BindingType::SampledTexture {
+        dimension: wgt::TextureViewDimension::D2,
+        component_type: wgt::TextureComponentType::Float,
+        multisampled: false,
+    };
+    let single = wgt::BindGroupLayoutEntry::new(0, wgt::ShaderStage::FRAGMENT, ty.clone());
+    let arrayed = wgt::BindGroupLayoutEntry {
+        count: Some(2),
+        ..wgt::BindGroupLayoutEntry::new(1, wgt::ShaderStage::FRAGMENT, ty)
+    };
+    let views = [TextureViewId::dummy(), TextureViewId::dummy()];
+
+    let view = BindingResource::TextureView(TextureViewId::dummy());
+    let view_array = BindingResource::TextureViewArray(&views);
+    assert!(check_resource_arrayness(0, &view, &single).is_ok());
+    assert!(check_resource_arrayness(1, &view_array, &arrayed).is_ok());
+    match check_resource_arrayness(1, &view, &arrayed) {
+        Err(CreateBindGroupError::WrongBindingType { binding: 1, .. }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match check_resource_arrayness(0, &view_array, &single) {
+        Err(CreateBindGroupError::WrongBindingType { binding: 0, .. }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_texture_view_array_length() {
+    let arrayed = wgt::BindGroupLayoutEntry {
+        count: Some(2),
+        ..wgt::BindGroupLayoutEntry::new(
+            3,
+            wgt::ShaderStage::FRAGMENT,
+            wgt::BindingType::SampledTexture {
+                dimension: wgt::TextureViewDimension::D2,
+                component_type: wgt::TextureComponentType::Float,
+                multisampled: false,
+            },
+        )
+    };
+    let views = [TextureViewId::dummy(); 3];
+
+    let too_short = BindingResource::TextureViewArray(&views[..1]);
+    match check_resource_arrayness(3, &too_short, &arrayed) {
+        Err(CreateBindGroupError::WrongArrayLength {
+            binding: 3,
+            actual: 1,
+            expected: 2,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    let too_long = BindingResource::TextureViewArray(&views);
+    match check_resource_arrayness(3, &too_long, &arrayed) {
+        Err(CreateBindGroupError::WrongArrayLength {
+            binding: 3,
+            actual: 3,
+            expected: 2,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let single = wgt::BindGroupLayoutEntry {
+        count: None,
+        ..arrayed
+    };
+    let view = BindingResource::TextureView(TextureViewId::dummy());
+    assert!(check_resource_arrayness(3, &view, &single).is_ok());
+}
+
+#[test]
+fn test_bind_group_layout_error_display() {
+    let errors = [
+        BindGroupLayoutError::ConflictBinding {
+            binding: 3,
+            existing: wgt::BindingType::Sampler { comparison: false },
+            incoming: wgt::BindingType::Sampler { comparison: true },
+        },
+        BindGroupLayoutError::MissingFeature(wgt::Features::SAMPLED_TEXTURE_BINDING_ARRAY),
+        BindGroupLayoutError::ZeroCount,
+        BindGroupLayoutError::ArrayUnsupported,
+    ];
+    for error in errors.iter() {
+        assert!(!error.to_string().is_empty());
+    }
+    assert!(errors[0].to_string().contains('3'));
+    assert!(errors[1]
+        .to_string()
+        .contains("SAMPLED_TEXTURE_BINDING_ARRAY"));
+}
+
+#[test]
+fn test_bind_error_display() {
+    let error = BindError::MismatchedDynamicOffsetCount {
+        actual: 1,
+        expected: 2,
+    };
+    assert!(error.to_string().contains("(1)"));
+    assert!(error.to_string().contains("(2)"));
+    let error = BindError::UnalignedDynamicBinding {
+        idx: 1,
+        offset: 3,
+        alignment: 256,
+    };
+    assert!(error.to_string().contains("index 1"));
+    assert!(error.to_string().contains("aligned to 256"));
+    let error = BindError::DynamicBindingOutOfBounds {
+        idx: 0,
+        offset: 512,
+        max: 256,
+    };
+    assert!(error.to_string().contains("maximum allowed offset: 256"));
+}
+
+#[test]
+fn test_bind_group_count() {
+    let limits = wgt::Limits {
+        max_bind_groups: MAX_BIND_GROUPS as u32 + 4,
+        ..wgt::Limits::default()
+    };
+    assert!(check_bind_group_count(MAX_BIND_GROUPS, &limits).is_ok());
+    match check_bind_group_count(MAX_BIND_GROUPS + 1, &limits) {
+        Err(PipelineLayoutError::TooManyGroups { actual, max }) => {
+            assert_eq!(actual, MAX_BIND_GROUPS + 1);
+            assert_eq!(max, MAX_BIND_GROUPS);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+    // Lower device limits still apply.
+    let limits = wgt::Limits::default();
+    let device_max = limits.max_bind_groups as usize;
+    match check_bind_group_count(device_max + 1, &limits) {
+        Err(PipelineLayoutError::TooManyGroups { max, .. }) => assert_eq!(max, device_max),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_pipeline_layout_error_display() {
+    let error = PipelineLayoutError::TooManyGroups { actual: 5, max: 4 };
+    assert!(error.to_string().contains('5'));
+    assert!(error.to_string().contains('4'));
+    let error = PipelineLayoutError::PushConstantRangeTooLarge {
+        index: 0,
+        range: 0..256,
+        max: 128,
+    };
+    assert!(error.to_string().contains("0..256"));
+    assert!(error.to_string().contains("0..128"));
+    let error = PipelineLayoutError::MisalignedPushConstantRange { index: 1, bound: 3 };
+    assert!(error.to_string().contains("bound 3"));
+}
+
+#[test]
+fn test_create_bind_group_error_display() {
+    let error = CreateBindGroupError::BindingsNumMismatch {
+        actual: 1,
+        expected: 3,
+    };
+    assert!(error.to_string().contains("(1)"));
+    assert!(error.to_string().contains("(3)"));
+    let error = CreateBindGroupError::MissingBindingDeclaration(7);
+    assert!(error.to_string().contains("binding 7"));
+    let error = CreateBindGroupError::WrongBindingType {
+        binding: 2,
+        actual: "sampler",
+        expected: "UniformBuffer",
+    };
+    assert!(error.to_string().contains("binding 2"));
+    assert!(error.to_string().contains("(sampler)"));
+    assert!(error.to_string().contains("UniformBuffer"));
+}
+
+#[test]
+fn test_all_dynamic_offset_errors() {
+    let infos = (0..4)
+        .map(|binding| BindGroupDynamicBindingData {
+            binding,
+            maximum_dynamic_offset: 1024,
+            required_alignment: wgt::BIND_BUFFER_ALIGNMENT,
+        })
+        .collect::<Vec<_>>();
+    let offsets = [256, 1, 2048, 257];
+    let errors = dynamic_offset_errors(&infos, &offsets).collect::<Vec<_>>();
+    assert_eq!(errors.len(), 3);
+    match errors[0] {
+        BindError::UnalignedDynamicBinding {
+            idx: 1, offset: 1, ..
+        } => {}
+        ref other => panic!("unexpected error: {:?}", other),
+    }
+    match errors[1] {
+        BindError::DynamicBindingOutOfBounds { idx: 2, .. } => {}
+        ref other => panic!("unexpected error: {:?}", other),
+    }
+    match errors[2] {
+        BindError::UnalignedDynamicBinding {
+            idx: 3,
+            offset: 257,
+            ..
+        } => {}
+        ref other => panic!("unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_per_binding_dynamic_offset_alignment() {
+    let infos = [
+        BindGroupDynamicBindingData {
+            binding: 0,
+            maximum_dynamic_offset: 1024,
+            required_alignment: 256,
+        },
+        BindGroupDynamicBindingData {
+            binding: 1,
+            maximum_dynamic_offset: 1024,
+            required_alignment: 32,
+        },
+    ];
+    let errors = dynamic_offset_errors(&infos, &[64, 64]).collect::<Vec<_>>();
+    assert_eq!(errors.len(), 1);
+    match errors[0] {
+        BindError::UnalignedDynamicBinding {
+            idx: 0,
+            offset: 64,
+            alignment: 256,
+        } => {}
+        ref other => panic!("unexpected error: {:?}", other),
+    }
+    assert_eq!(dynamic_offset_errors(&infos, &[256, 96]).count(), 0);
+}
+
+#[test]
+fn test_bind_group_layout_key_order_independent() {
+    use std::{
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+    };
+
+    let entries = [
+        wgt::BindGroupLayoutEntry::new(
+            0,
+            wgt::ShaderStage::VERTEX,
+            wgt::BindingType::UniformBuffer {
+                dynamic: true,
+                min_binding_size: None,
+            },
+        ),
+        wgt::BindGroupLayoutEntry::new(
+            1,
+            wgt::ShaderStage::FRAGMENT,
+            wgt::BindingType::Sampler { comparison: false },
+        ),
+    ];
+    let forward = entries
+        .iter()
+        .map(|entry| (entry.binding, entry.clone()))
+        .collect::<BindEntryMap>();
+    let backward = entries
+        .iter()
+        .rev()
+        .map(|entry| (entry.binding, entry.clone()))
+        .collect::<BindEntryMap>();
+
+    let hash = |key: &BindGroupLayoutKey| {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        hasher.finish()
+    };
+    let (key_a, key_b) = (
+        BindGroupLayoutKey::new(&forward),
+        BindGroupLayoutKey::new(&backward),
+    );
+    assert_eq!(key_a, key_b);
+    assert_eq!(hash(&key_a), hash(&key_b));
+}
+
+#[test]
+fn test_sampled_storage_format_conflict() {
+    use crate::id::TypedId as _;
+
+    let first = TextureId::zip(0, 1, wgt::Backend::Empty);
+    let second = TextureId::zip(1, 1, wgt::Backend::Empty);
+
+    let mut validator = TextureRoleValidator::default();
+    validator.add_view(first, wgt::TextureFormat::Rgba32Float, false);
+    validator.add_view(first, wgt::TextureFormat::Rgba32Float, true);
+    validator.add_view(second, wgt::TextureFormat::Bgra8Unorm, false);
+    assert!(validator.validate().is_ok());
+
+    validator.add_view(second, wgt::TextureFormat::Bgra8Unorm, true);
+    match validator.validate() {
+        Err(CreateBindGroupError::SampledStorageFormatConflict {
+            format: wgt::TextureFormat::Bgra8Unorm,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_bind_group_layout_builder_conflict() {
+    let entry = wgt::BindGroupLayoutEntry::new(
+        2,
+        wgt::ShaderStage::COMPUTE,
+        wgt::BindingType::Sampler { comparison: false },
+    );
+    match BindGroupLayoutBuilder::new()
+        .entry(entry.clone())
+        .and_then(|builder| builder.entry(entry))
+    {
+        Err(BindGroupLayoutError::ConflictBinding { binding: 2, .. }) => {}
+        other => panic!("unexpected result: {:?}", other.map(|b| b.finish())),
+    }
+}
+
+#[test]
+fn test_bind_group_layout_builder_conflict_types() {
+    let buffer = wgt::BindingType::UniformBuffer {
+        dynamic: false,
+        min_binding_size: None,
+    };
+    let sampler = wgt::BindingType::Sampler { comparison: false };
+    let result = BindGroupLayoutBuilder::new()
+        .entry(wgt::BindGroupLayoutEntry::new(
+            1,
+            wgt::ShaderStage::FRAGMENT,
+            buffer.clone(),
+        ))
+        .and_then(|builder| {
+            builder.entry(wgt::BindGroupLayoutEntry::new(
+                1,
+                wgt::ShaderStage::FRAGMENT,
+                sampler.clone(),
+            ))
+        });
+    let error = match result {
+        Err(error) => error,
+        Ok(builder) => panic!("unexpected layout: {:?}", builder.finish()),
+    };
+    match error {
+        BindGroupLayoutError::ConflictBinding {
+            binding: 1,
+            ref existing,
+            ref incoming,
+        } => {
+            assert_eq!(*existing, buffer);
+            assert_eq!(*incoming, sampler);
+        }
+        ref other => panic!("unexpected error: {:?}", other),
+    }
+    let message = error.to_string();
+    assert!(message.contains("UniformBuffer"));
+    assert!(message.contains("Sampler"));
+}
+
+#[test]
+fn test_bind_group_layout_builder_dynamic_count() {
+    let builder = BindGroupLayoutBuilder::new()
+        .entry(wgt::BindGroupLayoutEntry::new(
+            0,
+            wgt::ShaderStage::VERTEX,
+            wgt::BindingType::UniformBuffer {
+                dynamic: true,
+                min_binding_size: None,
+            },
+        ))
+        .and_then(|builder| {
+            builder.entry(wgt::BindGroupLayoutEntry::new(
+                1,
+                wgt::ShaderStage::VERTEX,
+                wgt::BindingType::UniformBuffer {
+                    dynamic: false,
+                    min_binding_size: None,
+                },
+            ))
+        })
+        .and_then(|builder| {
+            builder.entry(wgt::BindGroupLayoutEntry::new(
+                2,
+                wgt::ShaderStage::COMPUTE,
+                wgt::BindingType::StorageBuffer {
+                    dynamic: true,
+                    min_binding_size: None,
+                    readonly: false,
+                },
+            ))
+        })
+        .unwrap();
+    assert_eq!(builder.dynamic_count(), 2);
+    assert_eq!(builder.raw_bindings().len(), 3);
+    assert_eq!(builder.finish().len(), 3);
+}
+
+#[test]
+fn test_inconsistent_shared_binding() {
+    let sampled_texture = |binding, component_type| {
+        wgt::BindGroupLayoutEntry::new(
+            binding,
+            wgt::ShaderStage::FRAGMENT,
+            wgt::BindingType::SampledTexture {
+                dimension: wgt::TextureViewDimension::D2,
+                component_type,
+                multisampled: false,
+            },
+        )
+    };
+    let layout = |entries: Vec<wgt::BindGroupLayoutEntry>| {
+        entries
+            .into_iter()
+            .map(|entry| (entry.binding, entry))
+            .collect::<BindEntryMap>()
+    };
+    let first = layout(vec![
+        sampled_texture(0, wgt::TextureComponentType::Float),
+        sampled_texture(1, wgt::TextureComponentType::Float),
+    ]);
+    let second = layout(vec![sampled_texture(1, wgt::TextureComponentType::Float)]);
+    let third = layout(vec![sampled_texture(0, wgt::TextureComponentType::Uint)]);
+
+    assert!(check_layout_set_consistency(&[&first, &second]).is_ok());
+    match check_layout_set_consistency(&[&first, &second, &third]) {
+        Err(BindGroupLayoutError::InconsistentSharedBinding { binding: 0 }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_stale_layout() {
+    let layout = |ty| {
+        let entry = wgt::BindGroupLayoutEntry::new(0, wgt::ShaderStage::COMPUTE, ty);
+        let entries = std::iter::once((0, entry)).collect::<BindEntryMap>();
+        BindGroupLayoutKey::new(&entries).fingerprint()
+    };
+    let original = layout(wgt::BindingType::Sampler { comparison: false });
+    let recreated = layout(wgt::BindingType::Sampler { comparison: true });
+    assert_ne!(original, recreated);
+
+    assert!(check_layout_fingerprint(original, original).is_ok());
+    match check_layout_fingerprint(original, recreated) {
+        Err(BindError::StaleLayout) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_bound_coverage() {
+    let entries = std::iter::once((
+        0,
+        wgt::BindGroupLayoutEntry::new(
+            0,
+            wgt::ShaderStage::FRAGMENT,
+            wgt::BindingType::Sampler { comparison: false },
+        ),
+    ))
+    .collect::<BindEntryMap>();
+    let bound = [Some(&entries), None];
+
+    assert!(validate_bound_coverage(&[(0, 0)], &bound).is_ok());
+    match validate_bound_coverage(&[(0, 0), (0, 1)], &bound) {
+        Err(BindError::MissingBoundBinding { set: 0, binding: 1 }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match validate_bound_coverage(&[(1, 0)], &bound) {
+        Err(BindError::MissingBoundBinding { set: 1, binding: 0 }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_storage_binding_requires_storage_usage() {
+    let sampled_only = wgt::TextureUsage::SAMPLED | wgt::TextureUsage::COPY_DST;
+    assert!(check_bound_texture_usage(0, sampled_only, wgt::TextureUsage::SAMPLED).is_ok());
+    match check_bound_texture_usage(2, sampled_only, wgt::TextureUsage::STORAGE) {
+        Err(CreateBindGroupError::MissingTextureUsage { binding: 2, error }) => {
+            assert_eq!(error.actual, sampled_only);
+            assert_eq!(error.expected, wgt::TextureUsage::STORAGE);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+    let storage = wgt::TextureUsage::SAMPLED | wgt::TextureUsage::STORAGE;
+    assert!(check_bound_texture_usage(2, storage, wgt::TextureUsage::STORAGE).is_ok());
+}
+
+#[test]
+fn test_incomplete_cube_view() {
+    let sampled = |dimension| wgt::BindingType::SampledTexture {
+        dimension,
+        component_type: wgt::TextureComponentType::Float,
+        multisampled: false,
+    };
+    let cube = sampled(wgt::TextureViewDimension::Cube);
+    let cube_array = sampled(wgt::TextureViewDimension::CubeArray);
+    assert!(check_cube_view_layers(0, &cube, 6).is_ok());
+    assert!(check_cube_view_layers(0, &cube_array, 12).is_ok());
+    assert!(check_cube_view_layers(0, &sampled(wgt::TextureViewDimension::D2Array), 5).is_ok());
+    match check_cube_view_layers(1, &cube, 5) {
+        Err(CreateBindGroupError::IncompleteCubeView {
+            binding: 1,
+            layers: 5,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match check_cube_view_layers(2, &cube_array, 10) {
+        Err(CreateBindGroupError::IncompleteCubeView {
+            binding: 2,
+            layers: 10,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(check_cube_view_layers(2, &cube_array, 0).is_err());
+}
+
+#[test]
+fn test_dynamic_binding_mismatch() {
+    let mut entries = BindEntryMap::default();
+    for (binding, dynamic) in [(0, true), (1, false)].iter().cloned() {
+        entries.insert(
+            binding,
+            wgt::BindGroupLayoutEntry::new(
+                binding,
+                wgt::ShaderStage::COMPUTE,
+                wgt::BindingType::UniformBuffer {
+                    dynamic,
+                    min_binding_size: None,
+                },
+            ),
+        );
+    }
+    let info = |binding| BindGroupDynamicBindingData {
+        binding,
+        maximum_dynamic_offset: 0,
+        required_alignment: wgt::BIND_BUFFER_ALIGNMENT,
+    };
+    assert!(check_dynamic_bindings(&entries, &[info(0)]).is_ok());
+    // Only binding 0 is dynamic, but it's listed twice.
+    match check_dynamic_bindings(&entries, &[info(0), info(0)]) {
+        Err(CreateBindGroupError::DynamicBindingMismatch { expected, actual }) => {
+            assert_eq!(expected, vec![0]);
+            assert_eq!(actual, vec![0, 0]);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+    // Binding 1 isn't dynamic.
+    match check_dynamic_bindings(&entries, &[info(1)]) {
+        Err(CreateBindGroupError::DynamicBindingMismatch { expected, actual }) => {
+            assert_eq!(expected, vec![0]);
+            assert_eq!(actual, vec![1]);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_descriptor_counts() {
+    let mut entries = BindEntryMap::default();
+    let mut add = |binding, ty, count| {
+        let mut entry = wgt::BindGroupLayoutEntry::new(binding, wgt::ShaderStage::FRAGMENT, ty);
+        entry.count = count;
+        entries.insert(binding, entry);
+    };
+    add(
+        0,
+        wgt::BindingType::UniformBuffer {
+            dynamic: false,
+            min_binding_size: None,
+        },
+        None,
+    );
+    add(
+        1,
+        wgt::BindingType::StorageBuffer {
+            dynamic: false,
+            min_binding_size: None,
+            readonly: true,
+        },
+        None,
+    );
+    add(2, wgt::BindingType::Sampler { comparison: false }, None);
+    add(
+        3,
+        wgt::BindingType::SampledTexture {
+            dimension: wgt::TextureViewDimension::D2,
+            component_type: wgt::TextureComponentType::Float,
+            multisampled: false,
+        },
+        Some(4),
+    );
+
+    let (mut buffers, mut samplers, mut images) = (0, 0, 0);
+    for range in descriptor_counts(&entries).iter() {
+        match range.ty {
+            hal::pso::DescriptorType::Buffer { .. } => buffers += range.count,
+            hal::pso::DescriptorType::Sampler => samplers += range.count,
+            hal::pso::DescriptorType::Image { .. } => images += range.count,
+            _ => panic!("unexpected descriptor type: {:?}", range.ty),
+        }
+    }
+    assert_eq!((buffers, samplers, images), (2, 1, 4));
+}
+
+#[test]
+fn test_dynamic_offset_alignment() {
+    let limits = hal::Limits {
+        min_uniform_buffer_offset_alignment: 256,
+        min_storage_buffer_offset_alignment: 32,
+        ..hal::Limits::default()
+    };
+    let storage = |dynamic| wgt::BindingType::StorageBuffer {
+        dynamic,
+        min_binding_size: None,
+        readonly: false,
+    };
+    let uniform = wgt::BindingType::UniformBuffer {
+        dynamic: true,
+        min_binding_size: None,
+    };
+    assert_eq!(dynamic_offset_alignment(&storage(true), &limits), Some(32));
+    assert_eq!(dynamic_offset_alignment(&uniform, &limits), Some(256));
+    assert_eq!(dynamic_offset_alignment(&storage(false), &limits), None);
+}
+
+#[test]
+fn test_validate_bind_group() {
+    let mut layout = BindEntryMap::default();
+    layout.insert(
+        0,
+        wgt::BindGroupLayoutEntry::new(
+            0,
+            wgt::ShaderStage::FRAGMENT,
+            wgt::BindingType::Sampler { comparison: false },
+        ),
+    );
+    layout.insert(
+        1,
+        wgt::BindGroupLayoutEntry::new(
+            1,
+            wgt::ShaderStage::FRAGMENT,
+            wgt::BindingType::SampledTexture {
+                dimension: wgt::TextureViewDimension::D2,
+                component_type: wgt::TextureComponentType::Float,
+                multisampled: false,
+            },
+        ),
+    );
+    let sampler = |binding| BindGroupEntry {
+        binding,
+        resource: BindingResource::Sampler(SamplerId::dummy()),
+    };
+    let view = |binding| BindGroupEntry {
+        binding,
+        resource: BindingResource::TextureView(TextureViewId::dummy()),
+    };
+    let validate = |entries: &[BindGroupEntry]| {
+        let desc = BindGroupDescriptor {
+            label: None,
+            layout: BindGroupLayoutId::dummy(),
+            entries,
+        };
+        validate_bind_group(&desc, &layout)
+    };
+
+    assert!(validate(&[sampler(0), view(1)]).is_ok());
+    match validate(&[sampler(0)]) {
+        Err(CreateBindGroupError::BindingsNumMismatch {
+            actual: 1,
+            expected: 2,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match validate(&[sampler(0), view(2)]) {
+        Err(CreateBindGroupError::MissingBindingDeclaration(2)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    // Binding 1 isn't bound, even though the number of entries matches.
+    match validate(&[sampler(0), sampler(0)]) {
+        Err(CreateBindGroupError::DuplicateBinding(0)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match validate(&[view(0), view(1)]) {
+        Err(CreateBindGroupError::WrongBindingType {
+            binding: 0,
+            actual: "texture view",
+            expected: "sampler",
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_describe_binding_type() {
+    use wgt::BindingType as Bt;
+    let storage_buffer = |dynamic, readonly| Bt::StorageBuffer {
+        dynamic,
+        min_binding_size: None,
+        readonly,
+    };
+    let sampled = |dimension, multisampled| Bt::SampledTexture {
+        dimension,
+        component_type: wgt::TextureComponentType::Float,
+        multisampled,
+    };
+    let storage_texture = |readonly| Bt::StorageTexture {
+        dimension: wgt::TextureViewDimension::D2,
+        format: wgt::TextureFormat::Rgba8Unorm,
+        readonly,
+    };
+    let cases = [
+        (
+            Bt::UniformBuffer {
+                dynamic: false,
+                min_binding_size: None,
+            },
+            "uniform buffer",
+        ),
+        (
+            Bt::UniformBuffer {
+                dynamic: true,
+                min_binding_size: None,
+            },
+            "dynamic uniform buffer",
+        ),
+        (storage_buffer(false, false), "storage buffer"),
+        (storage_buffer(false, true), "read-only storage buffer"),
+        (storage_buffer(true, false), "dynamic storage buffer"),
+        (
+            storage_buffer(true, true),
+            "dynamic read-only storage buffer",
+        ),
+        (Bt::Sampler { comparison: false }, "sampler"),
+        (Bt::Sampler { comparison: true }, "comparison sampler"),
+        (
+            sampled(wgt::TextureViewDimension::D1, false),
+            "sampled 1D texture",
+        ),
+        (
+            sampled(wgt::TextureViewDimension::D2, false),
+            "sampled 2D texture",
+        ),
+        (
+            sampled(wgt::TextureViewDimension::D2Array, false),
+            "sampled 2D array texture",
+        ),
+        (
+            sampled(wgt::TextureViewDimension::Cube, false),
+            "sampled cube texture",
+        ),
+        (
+            sampled(wgt::TextureViewDimension::CubeArray, false),
+            "sampled cube array texture",
+        ),
+        (
+            sampled(wgt::TextureViewDimension::D3, false),
+            "sampled 3D texture",
+        ),
+        (
+            sampled(wgt::TextureViewDimension::D2, true),
+            "multisampled texture",
+        ),
+        (storage_texture(true), "read-only storage texture"),
+        (storage_texture(false), "writable storage texture"),
+        (
+            Bt::StorageTexelBuffer {
+                format: wgt::TextureFormat::R32Float,
+                readonly: true,
+            },
+            "read-only texel buffer",
+        ),
+    ];
+    for (ty, description) in cases.iter() {
+        assert_eq!(describe_binding_type(ty), *description);
+    }
+}
+
+#[test]
+fn test_sampler_comparison() {
+    let comparison = wgt::BindingType::Sampler { comparison: true };
+    let plain = wgt::BindingType::Sampler { comparison: false };
+    match check_sampler_comparison(4, false, &comparison) {
+        Err(CreateBindGroupError::WrongSamplerComparison { binding: 4 }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match check_sampler_comparison(5, true, &plain) {
+        Err(CreateBindGroupError::WrongSamplerComparison { binding: 5 }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(check_sampler_comparison(4, true, &comparison).is_ok());
+    assert!(check_sampler_comparison(5, false, &plain).is_ok());
+}
+
+#[test]
+fn test_view_dimension() {
+    let ty = wgt::BindingType::SampledTexture {
+        dimension: wgt::TextureViewDimension::D2,
+        component_type: wgt::TextureComponentType::Float,
+        multisampled: false,
+    };
+    match check_view_dimension(3, &ty, wgt::TextureViewDimension::Cube) {
+        Err(CreateBindGroupError::WrongViewDimension {
+            binding: 3,
+            actual: wgt::TextureViewDimension::Cube,
+            expected: wgt::TextureViewDimension::D2,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(check_view_dimension(3, &ty, wgt::TextureViewDimension::D2).is_ok());
+}
+
+#[test]
+fn test_validate_buffer_binding() {
+    let binding = |offset, size| BufferBinding {
+        buffer_id: BufferId::dummy(),
+        offset,
+        size: wgt::BufferSize::new(size),
+    };
+    let alignment = wgt::BIND_BUFFER_ALIGNMENT;
+    assert!(validate_buffer_binding(&binding(256, 256), 512, alignment).is_ok());
+    assert!(validate_buffer_binding(&binding(256, 0), 512, alignment).is_ok());
+
+    match validate_buffer_binding(&binding(768, 0), 512, alignment) {
+        Err(BindError::BufferRangeOutOfBounds {
+            offset: 768,
+            buffer_size: 512,
+            ..
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match validate_buffer_binding(&binding(256, 512), 512, alignment) {
+        Err(BindError::BufferRangeOutOfBounds {
+            offset: 256,
+            size: 512,
+            buffer_size: 512,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    // A binding without a size covers the rest of the buffer, which is empty
+    // at its very end.
+    match validate_buffer_binding(&binding(512, 0), 512, alignment) {
+        Err(BindError::EmptyBufferRange { offset: 512 }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match validate_buffer_binding(&binding(4, 0), 512, alignment) {
+        Err(BindError::UnalignedBufferOffset { offset: 4, .. }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_binding_size_too_small() {
+    let minimum = wgt::BufferSize::new(64);
+    match check_binding_size(2, 32, minimum) {
+        Err(CreateBindGroupError::BindingSizeTooSmall {
+            binding: 2,
+            provided: 32,
+            minimum: 64,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(check_binding_size(2, 64, minimum).is_ok());
+    assert!(check_binding_size(2, 4, None).is_ok());
+}
@@ -197,6 +197,12 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         .bind_groups
                         .use_extend(&*bind_group_guard, bind_group_id, (), ())
                         .unwrap();
+                    if let Some(pipeline_layout_id) = state.binder.pipeline_layout_id {
+                        bind_group.validate_layout(
+                            &pipeline_layout_guard[pipeline_layout_id],
+                            index as usize,
+                        )?;
+                    }
                     bind_group.validate_dynamic_bindings(offsets)?;
 
                     log::trace!(
@@ -266,7 +272,9 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         {
                             match entry.expect_layout(bgl_id.value) {
                                 LayoutChange::Match(bg_id, offsets) if is_compatible => {
-                                    let desc_set = bind_group_guard[bg_id].raw.raw();
+                                    let bind_group = &bind_group_guard[bg_id];
+                                    bind_group.validate_layout(pipeline_layout, index)?;
+                                    let desc_set = bind_group.raw.raw();
                                     unsafe {
                                         raw.bind_compute_descriptor_sets(
                                             &pipeline_layout.raw,
@@ -1043,6 +1043,14 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         .bind_groups
                         .use_extend(&*bind_group_guard, bind_group_id, (), ())
                         .unwrap();
+                    if let Some(pipeline_layout_id) = state.binder.pipeline_layout_id {
+                        bind_group
+                            .validate_layout(
+                                &pipeline_layout_guard[pipeline_layout_id],
+                                index as usize,
+                            )
+                            .map_err(RenderPassError::from)?;
+                    }
                     bind_group
                         .validate_dynamic_bindings(offsets)
                         .map_err(RenderPassError::from)?;
@@ -1120,7 +1128,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         {
                             match entry.expect_layout(bgl_id.value) {
                                 LayoutChange::Match(bg_id, offsets) if is_compatible => {
-                                    let desc_set = bind_group_guard[bg_id].raw.raw();
+                                    let bind_group = &bind_group_guard[bg_id];
+                                    bind_group
+                                        .validate_layout(pipeline_layout, index)
+                                        .map_err(RenderPassError::from)?;
+                                    let desc_set = bind_group.raw.raw();
                                     unsafe {
                                         raw.bind_graphics_descriptor_sets(
                                             &pipeline_layout.raw,
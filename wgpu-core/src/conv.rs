@@ -114,6 +114,12 @@ pub fn map_binding_type(binding: &wgt::BindGroupLayoutEntry) -> hal::pso::Descri
                 read_only: readonly,
             },
         },
+        Bt::StorageTexelBuffer { readonly, .. } => pso::DescriptorType::Buffer {
+            ty: pso::BufferDescriptorType::Storage {
+                read_only: readonly,
+            },
+            format: pso::BufferDescriptorFormat::Texel,
+        },
     }
 }
 
@@ -359,6 +365,7 @@ pub(crate) fn map_texture_format(
         // Packed 32 bit formats
         Tf::Rgb10a2Unorm => H::A2r10g10b10Unorm,
         Tf::Rg11b10Float => H::B10g11r11Ufloat,
+        Tf::Rgb9e5Ufloat => H::E5b9g9r9Ufloat,
 
         // Normal 64 bit formats
         Tf::Rg32Uint => H::Rg32Uint,
@@ -426,6 +433,10 @@ pub fn map_vertex_format(vertex_format: wgt::VertexFormat) -> hal::format::Forma
         Vf::Int2 => H::Rg32Sint,
         Vf::Int3 => H::Rgb32Sint,
         Vf::Int4 => H::Rgba32Sint,
+        Vf::Double => H::R64Sfloat,
+        Vf::Double2 => H::Rg64Sfloat,
+        Vf::Double3 => H::Rgb64Sfloat,
+        Vf::Double4 => H::Rgba64Sfloat,
     }
 }
 
@@ -88,6 +88,7 @@ struct NonReferencedResources<B: hal::Backend> {
     // Note: we keep the associated ID here in order to be able to check
     // at any point what resources are used in a submission.
     image_views: Vec<(id::TextureViewId, B::ImageView)>,
+    buffer_views: Vec<B::BufferView>,
     samplers: Vec<B::Sampler>,
     framebuffers: Vec<B::Framebuffer>,
     desc_sets: Vec<DescriptorSet<B>>,
@@ -103,6 +104,7 @@ impl<B: hal::Backend> NonReferencedResources<B> {
             buffers: Vec::new(),
             images: Vec::new(),
             image_views: Vec::new(),
+            buffer_views: Vec::new(),
             samplers: Vec::new(),
             framebuffers: Vec::new(),
             desc_sets: Vec::new(),
@@ -117,6 +119,7 @@ impl<B: hal::Backend> NonReferencedResources<B> {
         self.buffers.extend(other.buffers);
         self.images.extend(other.images);
         self.image_views.extend(other.image_views);
+        self.buffer_views.extend(other.buffer_views);
         self.samplers.extend(other.samplers);
         self.framebuffers.extend(other.framebuffers);
         self.desc_sets.extend(other.desc_sets);
@@ -151,6 +154,9 @@ impl<B: hal::Backend> NonReferencedResources<B> {
         for (_, raw) in self.image_views.drain(..) {
             device.destroy_image_view(raw);
         }
+        for raw in self.buffer_views.drain(..) {
+            device.destroy_buffer_view(raw);
+        }
         for raw in self.samplers.drain(..) {
             device.destroy_sampler(raw);
         }
@@ -373,12 +379,13 @@ impl<B: GfxBackend> LifetimeTracker<B> {
                     self.suspected_resources.add_trackers(&res.used);
 
                     let submit_index = res.life_guard.submission_index.load(Ordering::Acquire);
-                    self.active
+                    let resources = self
+                        .active
                         .iter_mut()
                         .find(|a| a.index == submit_index)
-                        .map_or(&mut self.free_resources, |a| &mut a.last_resources)
-                        .desc_sets
-                        .push(res.raw);
+                        .map_or(&mut self.free_resources, |a| &mut a.last_resources);
+                    resources.desc_sets.push(res.raw);
+                    resources.buffer_views.extend(res.buffer_views);
                 }
             }
         }
@@ -590,6 +590,9 @@ impl<B: hal::Backend> Device<B> {
     pub(crate) fn destroy_bind_group(&self, bind_group: binding_model::BindGroup<B>) {
         unsafe {
             self.desc_allocator.lock().free(iter::once(bind_group.raw));
+            for raw in bind_group.buffer_views {
+                self.raw.destroy_buffer_view(raw);
+            }
         }
     }
 
@@ -983,7 +986,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let texture = &texture_guard[texture_id];
         let device = &device_guard[texture.device_id.value];
 
-        let (format, view_kind, range) = match desc {
+        let (format, dimension, view_kind, range) = match desc {
             Some(desc) => {
                 let kind = conv::map_texture_view_dimension(desc.dimension);
                 let end_level = if desc.level_count == 0 {
@@ -1001,17 +1004,28 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     levels: desc.base_mip_level as u8..end_level,
                     layers: desc.base_array_layer as u16..end_layer,
                 };
-                (desc.format, kind, range)
+                (desc.format, desc.dimension, kind, range)
             }
             None => {
-                let kind = match texture.kind {
-                    hal::image::Kind::D1(_, 1) => hal::image::ViewKind::D1,
-                    hal::image::Kind::D1(..) => hal::image::ViewKind::D1Array,
-                    hal::image::Kind::D2(_, _, 1, _) => hal::image::ViewKind::D2,
-                    hal::image::Kind::D2(..) => hal::image::ViewKind::D2Array,
-                    hal::image::Kind::D3(..) => hal::image::ViewKind::D3,
+                let (dimension, kind) = match texture.kind {
+                    hal::image::Kind::D1(_, 1) => {
+                        (wgt::TextureViewDimension::D1, hal::image::ViewKind::D1)
+                    }
+                    hal::image::Kind::D1(..) => {
+                        (wgt::TextureViewDimension::D1, hal::image::ViewKind::D1Array)
+                    }
+                    hal::image::Kind::D2(_, _, 1, _) => {
+                        (wgt::TextureViewDimension::D2, hal::image::ViewKind::D2)
+                    }
+                    hal::image::Kind::D2(..) => (
+                        wgt::TextureViewDimension::D2Array,
+                        hal::image::ViewKind::D2Array,
+                    ),
+                    hal::image::Kind::D3(..) => {
+                        (wgt::TextureViewDimension::D3, hal::image::ViewKind::D3)
+                    }
                 };
-                (texture.format, kind, texture.full_range.clone())
+                (texture.format, dimension, kind, texture.full_range.clone())
             }
         };
 
@@ -1037,6 +1051,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 },
             },
             format: texture.format,
+            dimension,
             extent: texture.kind.extent().at_level(range.levels.start),
             samples: texture.kind.num_samples(),
             range,
@@ -1200,24 +1215,21 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
 
         let mut token = Token::root();
         let hub = B::hub(self);
-        let mut entry_map = FastHashMap::default();
+        let mut builder = binding_model::BindGroupLayoutBuilder::new();
         for entry in desc.entries {
-            if entry_map.insert(entry.binding, entry.clone()).is_some() {
-                return Err(binding_model::BindGroupLayoutError::ConflictBinding(
-                    entry.binding,
-                ));
-            }
+            builder = builder.entry(entry.clone())?;
         }
 
         let (device_guard, mut token) = hub.devices.read(&mut token);
         let device = &device_guard[device_id];
 
         // If there is an equivalent BGL, just bump the refcount and return it.
+        let key = binding_model::BindGroupLayoutKey::new(builder.entries());
         {
             let (bgl_guard, _) = hub.bind_group_layouts.read(&mut token);
             let bind_group_layout_id = bgl_guard
                 .iter(device_id.backend())
-                .find(|(_, bgl)| bgl.entries == entry_map);
+                .find(|(_, bgl)| bgl.key == key);
 
             if let Some((id, value)) = bind_group_layout_id {
                 value.multi_ref_count.inc();
@@ -1225,52 +1237,16 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             }
         }
 
-        // Validate the count parameter
-        for binding in desc
-            .entries
-            .iter()
-            .filter(|binding| binding.count.is_some())
-        {
-            if let Some(count) = binding.count {
-                if count == 0 {
-                    return Err(binding_model::BindGroupLayoutError::ZeroCount);
-                }
-                match binding.ty {
-                    wgt::BindingType::SampledTexture { .. } => {
-                        if !device
-                            .features
-                            .contains(wgt::Features::SAMPLED_TEXTURE_BINDING_ARRAY)
-                        {
-                            return Err(binding_model::BindGroupLayoutError::MissingFeature(
-                                wgt::Features::SAMPLED_TEXTURE_BINDING_ARRAY,
-                            ));
-                        }
-                    }
-                    _ => return Err(binding_model::BindGroupLayoutError::ArrayUnsupported),
-                }
-            } else {
-                unreachable!() // programming bug
-            }
+        // Validate the binding type and the count parameter
+        for binding in desc.entries {
+            binding_model::check_binding_type_feature(binding, device.features)?;
+            binding_model::check_binding_count(binding, device.features, &device.limits)?;
         }
 
-        let raw_bindings = desc
-            .entries
-            .iter()
-            .map(|binding| hal::pso::DescriptorSetLayoutBinding {
-                binding: binding.binding,
-                ty: conv::map_binding_type(binding),
-                count: binding
-                    .count
-                    .map_or(1, |v| v as hal::pso::DescriptorArrayIndex), //TODO: consolidate
-                stage_flags: conv::map_shader_stage_flags(binding.visibility),
-                immutable_samplers: false, // TODO
-            })
-            .collect::<Vec<_>>(); //TODO: avoid heap allocation
-
         let raw = unsafe {
             let mut raw_layout = device
                 .raw
-                .create_descriptor_set_layout(&raw_bindings, &[])
+                .create_descriptor_set_layout(builder.raw_bindings(), &[])
                 .unwrap();
             if let Some(label) = desc.label {
                 device
@@ -1297,13 +1273,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 ref_count: device.life_guard.add_ref(),
             },
             multi_ref_count: MultiRefCount::new(),
-            entries: entry_map,
-            desc_counts: raw_bindings.iter().cloned().collect(),
-            dynamic_count: desc
-                .entries
-                .iter()
-                .filter(|b| b.has_dynamic_offset())
-                .count(),
+            desc_counts: builder.desc_counts(),
+            dynamic_count: builder.dynamic_count(),
+            fingerprint: key.fingerprint(),
+            key,
+            entries: builder.finish(),
             count_validator,
         };
 
@@ -1363,55 +1337,13 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
 
         let (device_guard, mut token) = hub.devices.read(&mut token);
         let device = &device_guard[device_id];
-        let bind_group_layouts_count = desc.bind_group_layouts.len();
-        let device_max_bind_groups = device.limits.max_bind_groups as usize;
-        if bind_group_layouts_count > device_max_bind_groups {
-            return Err(PipelineLayoutError::TooManyGroups {
-                actual: bind_group_layouts_count,
-                max: device_max_bind_groups,
-            });
-        }
-
-        if !desc.push_constant_ranges.is_empty()
-            && !device.features.contains(wgt::Features::PUSH_CONSTANTS)
-        {
-            return Err(PipelineLayoutError::MissingFeature(
-                wgt::Features::PUSH_CONSTANTS,
-            ));
-        }
-        let mut used_stages = wgt::ShaderStage::empty();
-        for (index, pc) in desc.push_constant_ranges.iter().enumerate() {
-            if pc.stages.intersects(used_stages) {
-                return Err(PipelineLayoutError::MoreThanOnePushConstantRangePerStage {
-                    index,
-                    provided: pc.stages,
-                    intersected: pc.stages & used_stages,
-                });
-            }
-            used_stages |= pc.stages;
-
-            let device_max_pc_size = device.limits.max_push_constant_size;
-            if device_max_pc_size < pc.range.end {
-                return Err(PipelineLayoutError::PushConstantRangeTooLarge {
-                    index,
-                    range: pc.range.clone(),
-                    max: device_max_pc_size,
-                });
-            }
+        binding_model::check_bind_group_count(desc.bind_group_layouts.len(), &device.limits)?;
 
-            if pc.range.start % wgt::PUSH_CONSTANT_ALIGNMENT != 0 {
-                return Err(PipelineLayoutError::MisalignedPushConstantRange {
-                    index,
-                    bound: pc.range.start,
-                });
-            }
-            if pc.range.end % wgt::PUSH_CONSTANT_ALIGNMENT != 0 {
-                return Err(PipelineLayoutError::MisalignedPushConstantRange {
-                    index,
-                    bound: pc.range.end,
-                });
-            }
-        }
+        binding_model::check_push_constant_ranges(
+            desc.push_constant_ranges,
+            device.features,
+            &device.limits,
+        )?;
 
         let mut count_validator = binding_model::BindingTypeMaxCountValidator::default();
         let pipeline_layout = {
@@ -1439,6 +1371,24 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             .validate(&device.limits)
             .map_err(PipelineLayoutError::TooManyBindings)?;
 
+        let (bind_group_layout_ids, bind_group_layout_fingerprints) = {
+            let (bind_group_layout_guard, _) = hub.bind_group_layouts.read(&mut token);
+            let ids = desc
+                .bind_group_layouts
+                .iter()
+                .map(|&id| Stored {
+                    value: id,
+                    ref_count: bind_group_layout_guard[id].multi_ref_count.add_ref(),
+                })
+                .collect();
+            let fingerprints = desc
+                .bind_group_layouts
+                .iter()
+                .map(|&id| bind_group_layout_guard[id].fingerprint)
+                .collect();
+            (ids, fingerprints)
+        };
+
         let layout = binding_model::PipelineLayout {
             raw: pipeline_layout,
             device_id: Stored {
@@ -1446,16 +1396,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 ref_count: device.life_guard.add_ref(),
             },
             life_guard: LifeGuard::new(),
-            bind_group_layout_ids: {
-                let (bind_group_layout_guard, _) = hub.bind_group_layouts.read(&mut token);
-                desc.bind_group_layouts
-                    .iter()
-                    .map(|&id| Stored {
-                        value: id,
-                        ref_count: bind_group_layout_guard[id].multi_ref_count.add_ref(),
-                    })
-                    .collect()
-            },
+            bind_group_layout_ids,
+            bind_group_layout_fingerprints,
             push_constant_ranges: desc.push_constant_ranges.iter().cloned().collect(),
         };
 
@@ -1517,13 +1459,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         let (bind_group_layout_guard, mut token) = hub.bind_group_layouts.read(&mut token);
         let bind_group_layout = &bind_group_layout_guard[desc.layout];
 
-        // Check that the number of entries in the descriptor matches
-        // the number of entries in the layout.
-        let actual = desc.entries.len();
-        let expected = bind_group_layout.entries.len();
-        if actual != expected {
-            return Err(CreateBindGroupError::BindingsNumMismatch { expected, actual });
-        }
+        binding_model::validate_bind_group(desc, &bind_group_layout.entries)?;
 
         let mut desc_set = {
             let mut desc_sets = ArrayVec::<[_; 1]>::new();
@@ -1556,6 +1492,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
         // TODO: arrayvec/smallvec
         // Record binding info for dynamic offset validation
         let mut dynamic_binding_info = Vec::new();
+        // Texel buffers are bound through views, which have to outlive the writes.
+        let mut texel_buffer_views = Vec::new();
 
         // fill out the descriptors
         let mut used = TrackerSet::new(B::VARIANT);
@@ -1567,27 +1505,25 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
 
             //TODO: group writes into contiguous sections
             let mut writes = Vec::new();
+            let mut texture_roles = binding_model::TextureRoleValidator::default();
             for entry in desc.entries {
                 let binding = entry.binding;
-                // Find the corresponding declaration in the layout
-                let decl = bind_group_layout
-                    .entries
-                    .get(&binding)
-                    .ok_or(CreateBindGroupError::MissingBindingDeclaration(binding))?;
+                // The declaration is known to exist and fit the resource.
+                let decl = &bind_group_layout.entries[&binding];
                 let descriptors: SmallVec<[_; 1]> = match entry.resource {
                     Br::Buffer(ref bb) => {
-                        let (pub_usage, internal_use, min_size, dynamic) = match decl.ty {
+                        let (pub_usage, internal_use, min_size, texel_format) = match decl.ty {
                             wgt::BindingType::UniformBuffer {
-                                dynamic,
+                                dynamic: _,
                                 min_binding_size,
                             } => (
                                 wgt::BufferUsage::UNIFORM,
                                 resource::BufferUse::UNIFORM,
                                 min_binding_size,
-                                dynamic,
+                                None,
                             ),
                             wgt::BindingType::StorageBuffer {
-                                dynamic,
+                                dynamic: _,
                                 min_binding_size,
                                 readonly,
                             } => (
@@ -1598,25 +1534,33 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                     resource::BufferUse::STORAGE_LOAD
                                 },
                                 min_binding_size,
-                                dynamic,
+                                None,
+                            ),
+                            wgt::BindingType::StorageTexelBuffer { format, readonly } => (
+                                wgt::BufferUsage::STORAGE,
+                                if readonly {
+                                    resource::BufferUse::STORAGE_LOAD
+                                } else {
+                                    resource::BufferUse::STORAGE_STORE
+                                },
+                                None,
+                                Some(format),
                             ),
                             _ => {
                                 return Err(CreateBindGroupError::WrongBindingType {
                                     binding,
-                                    actual: decl.ty.clone(),
-                                    expected:
-                                        "UniformBuffer, StorageBuffer or ReadonlyStorageBuffer",
+                                    actual: binding_model::describe_binding_resource(
+                                        &entry.resource,
+                                    ),
+                                    expected: binding_model::describe_binding_type(&decl.ty),
                                 })
                             }
                         };
 
-                        assert_eq!(
-                            bb.offset % wgt::BIND_BUFFER_ALIGNMENT,
-                            0,
-                            "Buffer offset {} must be a multiple of BIND_BUFFER_ALIGNMENT",
-                            bb.offset
-                        );
-
+                        binding_model::check_resource_alive(
+                            binding,
+                            &buffer_guard[bb.buffer_id].life_guard,
+                        )?;
                         let buffer = used
                             .buffers
                             .use_extend(&*buffer_guard, bb.buffer_id, (), internal_use)
@@ -1627,17 +1571,16 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             buffer.usage,
                             pub_usage
                         );
+                        binding_model::validate_buffer_binding(
+                            bb,
+                            buffer.size,
+                            wgt::BIND_BUFFER_ALIGNMENT,
+                        )
+                        .map_err(|error| {
+                            CreateBindGroupError::InvalidBufferBinding { binding, error }
+                        })?;
                         let (bind_size, bind_end) = match bb.size {
-                            Some(size) => {
-                                let end = bb.offset + size.get();
-                                assert!(
-                                    end <= buffer.size,
-                                    "Bound buffer range {:?} does not fit in buffer size {}",
-                                    bb.offset..end,
-                                    buffer.size
-                                );
-                                (size.get(), end)
-                            }
+                            Some(size) => (size.get(), bb.offset + size.get()),
                             None => (buffer.size - bb.offset, buffer.size),
                         };
 
@@ -1648,61 +1591,93 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         }
 
                         // Record binding info for validating dynamic offsets
-                        if dynamic {
+                        if let Some(required_alignment) =
+                            binding_model::dynamic_offset_alignment(&decl.ty, &device.hal_limits)
+                        {
                             dynamic_binding_info.push(binding_model::BindGroupDynamicBindingData {
+                                binding,
                                 maximum_dynamic_offset: buffer.size - bind_end,
+                                required_alignment,
                             });
                         }
 
-                        match min_size {
-                            Some(non_zero) if non_zero.get() > bind_size => panic!(
-                                "Minimum buffer binding size {} is not respected with size {}",
-                                non_zero.get(),
-                                bind_size
-                            ),
-                            _ => (),
-                        }
+                        binding_model::check_binding_size(binding, bind_size, min_size)?;
 
                         let sub_range = hal::buffer::SubRange {
                             offset: bb.offset,
                             size: Some(bind_size),
                         };
+                        if let Some(format) = texel_format {
+                            let raw = unsafe {
+                                device
+                                    .raw
+                                    .create_buffer_view(
+                                        &buffer.raw,
+                                        Some(conv::map_texture_format(
+                                            format,
+                                            device.private_features,
+                                        )),
+                                        sub_range,
+                                    )
+                                    .unwrap()
+                            };
+                            // The descriptor is written once all the views exist.
+                            texel_buffer_views.push((binding, raw));
+                            continue;
+                        }
                         SmallVec::from([hal::pso::Descriptor::Buffer(&buffer.raw, sub_range)])
                     }
                     Br::Sampler(id) => {
                         match decl.ty {
-                            wgt::BindingType::Sampler { comparison } => {
+                            wgt::BindingType::Sampler { .. } => {
+                                binding_model::check_resource_alive(
+                                    binding,
+                                    &sampler_guard[id].life_guard,
+                                )?;
                                 let sampler = used
                                     .samplers
                                     .use_extend(&*sampler_guard, id, (), ())
                                     .unwrap();
 
                                 // Check the actual sampler to also (not) be a comparison sampler
-                                if sampler.comparison != comparison {
-                                    return Err(CreateBindGroupError::WrongSamplerComparison);
-                                }
+                                binding_model::check_sampler_comparison(
+                                    binding,
+                                    sampler.comparison,
+                                    &decl.ty,
+                                )?;
 
                                 SmallVec::from([hal::pso::Descriptor::Sampler(&sampler.raw)])
                             }
                             _ => {
                                 return Err(CreateBindGroupError::WrongBindingType {
                                     binding,
-                                    actual: decl.ty.clone(),
-                                    expected: "Sampler",
+                                    actual: binding_model::describe_binding_resource(
+                                        &entry.resource,
+                                    ),
+                                    expected: binding_model::describe_binding_type(&decl.ty),
                                 })
                             }
                         }
                     }
                     Br::TextureView(id) => {
+                        binding_model::check_resource_alive(
+                            binding,
+                            &texture_view_guard[id].life_guard,
+                        )?;
                         let view = used
                             .views
                             .use_extend(&*texture_view_guard, id, (), ())
                             .unwrap();
+                        binding_model::check_cube_view_layers(
+                            binding,
+                            &decl.ty,
+                            (view.range.layers.end - view.range.layers.start) as u32,
+                        )?;
+                        binding_model::check_view_dimension(binding, &decl.ty, view.dimension)?;
                         let (pub_usage, internal_use) = match decl.ty {
-                            wgt::BindingType::SampledTexture { .. } => (
-                                wgt::TextureUsage::SAMPLED,
-                                resource::TextureUse::SAMPLED,
-                            ),
+                            wgt::BindingType::SampledTexture { .. } => {
+                                (wgt::TextureUsage::SAMPLED, resource::TextureUse::SAMPLED)
+                            }
                             wgt::BindingType::StorageTexture { readonly, .. } => (
                                 wgt::TextureUsage::STORAGE,
                                 if readonly {
@@ -1711,11 +1686,15 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                     resource::TextureUse::STORAGE_STORE
                                 },
                             ),
-                            _ => return Err(CreateBindGroupError::WrongBindingType {
-                                binding,
-                                actual: decl.ty.clone(),
-                                expected: "SampledTexture, ReadonlyStorageTexture or WriteonlyStorageTexture"
-                            })
+                            _ => {
+                                return Err(CreateBindGroupError::WrongBindingType {
+                                    binding,
+                                    actual: binding_model::describe_binding_resource(
+                                        &entry.resource,
+                                    ),
+                                    expected: binding_model::describe_binding_type(&decl.ty),
+                                })
+                            }
                         };
                         match view.inner {
                             resource::TextureViewInner::Native {
@@ -1725,6 +1704,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                 // Careful here: the texture may no longer have its own ref count,
                                 // if it was deleted by the user.
                                 let texture = &texture_guard[source_id.value];
+                                texture_roles.add_view(
+                                    source_id.value,
+                                    view.format,
+                                    pub_usage == wgt::TextureUsage::STORAGE,
+                                );
                                 used.textures
                                     .change_extend(
                                         source_id.value,
@@ -1733,12 +1717,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                         internal_use,
                                     )
                                     .unwrap();
-                                assert!(
-                                    texture.usage.contains(pub_usage),
-                                    "Texture usage {:?} must contain usage flag(s) {:?}",
+                                binding_model::check_bound_texture_usage(
+                                    binding,
                                     texture.usage,
-                                    pub_usage
-                                );
+                                    pub_usage,
+                                )?;
                                 let image_layout =
                                     conv::map_texture_state(internal_use, view.range.aspects).1;
                                 SmallVec::from([hal::pso::Descriptor::Image(raw, image_layout)])
@@ -1754,20 +1737,6 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             "Feature SAMPLED_TEXTURE_BINDING_ARRAY must be enabled to use TextureViewArrays in a bind group"
                         );
 
-                        if let Some(count) = decl.count {
-                            assert_eq!(
-                                count as usize,
-                                bindings_array.len(),
-                                "Binding count declared with {} items, but {} items were provided",
-                                count,
-                                bindings_array.len()
-                            );
-                        } else {
-                            panic!(
-                                "Binding declared as a single item, but bind group is using it as an array",
-                            );
-                        }
-
                         let (pub_usage, internal_use) = match decl.ty {
                             wgt::BindingType::SampledTexture { .. } => {
                                 (wgt::TextureUsage::SAMPLED, resource::TextureUse::SAMPLED)
@@ -1775,11 +1744,19 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             _ => {
                                 return Err(CreateBindGroupError::WrongBindingType {
                                     binding,
-                                    actual: decl.ty.clone(),
-                                    expected: "SampledTextureArray",
+                                    actual: binding_model::describe_binding_resource(
+                                        &entry.resource,
+                                    ),
+                                    expected: binding_model::describe_binding_type(&decl.ty),
                                 })
                             }
                         };
+                        for &id in bindings_array.iter() {
+                            binding_model::check_resource_alive(
+                                binding,
+                                &texture_view_guard[id].life_guard,
+                            )?;
+                        }
                         bindings_array
                             .iter()
                             .map(|&id| {
@@ -1787,6 +1764,16 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                     .views
                                     .use_extend(&*texture_view_guard, id, (), ())
                                     .unwrap();
+                                binding_model::check_cube_view_layers(
+                                    binding,
+                                    &decl.ty,
+                                    (view.range.layers.end - view.range.layers.start) as u32,
+                                )?;
+                                binding_model::check_view_dimension(
+                                    binding,
+                                    &decl.ty,
+                                    view.dimension,
+                                )?;
                                 match view.inner {
                                     resource::TextureViewInner::Native {
                                         ref raw,
@@ -1795,6 +1782,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                         // Careful here: the texture may no longer have its own ref count,
                                         // if it was deleted by the user.
                                         let texture = &texture_guard[source_id.value];
+                                        texture_roles.add_view(source_id.value, view.format, false);
                                         used.textures
                                             .change_extend(
                                                 source_id.value,
@@ -1803,25 +1791,24 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                                                 internal_use,
                                             )
                                             .unwrap();
-                                        assert!(
-                                            texture.usage.contains(pub_usage),
-                                            "Texture usage {:?} must contain usage flag(s) {:?}",
+                                        binding_model::check_bound_texture_usage(
+                                            binding,
                                             texture.usage,
-                                            pub_usage
-                                        );
+                                            pub_usage,
+                                        )?;
                                         let image_layout = conv::map_texture_state(
                                             internal_use,
                                             view.range.aspects,
                                         )
                                         .1;
-                                        hal::pso::Descriptor::Image(raw, image_layout)
+                                        Ok(hal::pso::Descriptor::Image(raw, image_layout))
                                     }
                                     resource::TextureViewInner::SwapChain { .. } => panic!(
                                         "Unable to create a bind group with a swap chain image"
                                     ),
                                 }
                             })
-                            .collect()
+                            .collect::<Result<_, CreateBindGroupError>>()?
                     }
                 };
                 writes.alloc().init(hal::pso::DescriptorSetWrite {
@@ -1832,6 +1819,23 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 });
             }
 
+            for &(binding, ref raw) in texel_buffer_views.iter() {
+                writes.alloc().init(hal::pso::DescriptorSetWrite {
+                    set: desc_set.raw(),
+                    binding,
+                    array_offset: 0,
+                    descriptors: SmallVec::from([hal::pso::Descriptor::TexelBuffer(raw)]),
+                });
+            }
+
+            texture_roles.validate()?;
+            // Entries may come in any order, but dynamic offsets are given in binding order.
+            dynamic_binding_info.sort_by_key(|info| info.binding);
+            binding_model::check_dynamic_bindings(
+                &bind_group_layout.entries,
+                &dynamic_binding_info,
+            )?;
+
             unsafe {
                 device.raw.write_descriptor_sets(writes);
             }
@@ -1844,9 +1848,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 ref_count: device.life_guard.add_ref(),
             },
             layout_id: desc.layout,
+            layout_fingerprint: bind_group_layout.fingerprint,
             life_guard: LifeGuard::new(),
             used,
             dynamic_binding_info,
+            buffer_views: texel_buffer_views.into_iter().map(|(_, raw)| raw).collect(),
         };
         let ref_count = bind_group.life_guard.add_ref();
 
@@ -2156,8 +2162,11 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             &rasterization_state.cloned().unwrap_or_default(),
         );
 
-        let mut interface = validation::StageInterface::default();
+        let mut interface =
+            validation::vertex_buffer_interface(desc.vertex_state.vertex_buffers, device.features)
+                .map_err(pipeline::RenderPipelineError::VertexFormat)?;
         let mut validated_stages = wgt::ShaderStage::empty();
+        let mut shader_writes_depth = false;
 
         let desc_vbs = desc.vertex_state.vertex_buffers;
         let mut vertex_strides = Vec::with_capacity(desc_vbs.len());
@@ -2170,6 +2179,8 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             if vb_state.attributes.is_empty() {
                 continue;
             }
+            validation::check_vertex_buffer_layout(vb_state)
+                .map_err(|error| pipeline::RenderPipelineError::VertexBuffer { index: i, error })?;
             vertex_buffers.alloc().init(hal::pso::VertexBufferDesc {
                 binding: i as u32,
                 stride: vb_state.stride as u32,
@@ -2196,10 +2207,6 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         offset: attribute.offset as u32,
                     },
                 });
-                interface.insert(
-                    attribute.shader_location,
-                    validation::MaybeOwned::Owned(validation::map_vertex_format(attribute.format)),
-                );
             }
         }
 
@@ -2251,6 +2258,12 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 .iter()
                 .map(|id| &bgl_guard[id.value].entries)
                 .collect::<ArrayVec<[&binding_model::BindEntryMap; MAX_BIND_GROUPS]>>();
+            let stage_env = validation::StageEnvironment {
+                group_layouts: &group_layouts,
+                push_constant_ranges: &layout.push_constant_ranges,
+                features: device.features,
+                limits: device.limits.clone(),
+            };
 
             let (shader_module_guard, _) = hub.shader_modules.read(&mut token);
 
@@ -2291,6 +2304,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 }),
             };
 
+            let mut unwritten_vertex_outputs = Vec::new();
             let vertex = {
                 let entry_point_name = desc.vertex_stage.entry_point;
 
@@ -2300,12 +2314,18 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     let flag = wgt::ShaderStage::VERTEX;
                     interface = validation::check_stage(
                         module,
-                        &group_layouts,
+                        &stage_env,
                         entry_point_name,
                         ExecutionModel::Vertex,
                         interface,
                     )
                     .map_err(|error| pipeline::RenderPipelineError::Stage { flag, error })?;
+                    unwritten_vertex_outputs = validation::unwritten_outputs(
+                        module,
+                        entry_point_name,
+                        ExecutionModel::Vertex,
+                    )
+                    .map_err(|error| pipeline::RenderPipelineError::Stage { flag, error })?;
                     validated_stages |= flag;
                 }
 
@@ -2325,9 +2345,28 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                     if validated_stages == wgt::ShaderStage::VERTEX {
                         if let Some(ref module) = shader_module.module {
                             let flag = wgt::ShaderStage::FRAGMENT;
+                            let inputs = validation::input_locations(
+                                module,
+                                entry_point_name,
+                                ExecutionModel::Fragment,
+                            )
+                            .map_err(|error| {
+                                pipeline::RenderPipelineError::Stage { flag, error }
+                            })?;
+                            // Unwritten outputs that the fragment stage reads are missing
+                            // from the vertex interface, so `check_stage` rejects those.
+                            for location in unwritten_vertex_outputs
+                                .iter()
+                                .filter(|location| !inputs.contains(location))
+                            {
+                                log::warn!(
+                                    "Vertex output[{}] is declared, but never written nor read by the fragment stage",
+                                    location
+                                );
+                            }
                             interface = validation::check_stage(
                                 module,
-                                &group_layouts,
+                                &stage_env,
                                 entry_point_name,
                                 ExecutionModel::Fragment,
                                 interface,
@@ -2335,6 +2374,10 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                             .map_err(|error| {
                                 pipeline::RenderPipelineError::Stage { flag, error }
                             })?;
+                            shader_writes_depth =
+                                validation::writes_frag_depth(module, entry_point_name).map_err(
+                                    |error| pipeline::RenderPipelineError::Stage { flag, error },
+                                )?;
                             validated_stages |= flag;
                         }
                     }
@@ -2348,21 +2391,42 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 None => None,
             };
 
+            validation::check_depth_stencil_format(
+                depth_stencil_state.map(|state| state.format),
+                shader_writes_depth,
+                depth_stencil_state.map_or(false, |state| {
+                    state.stencil_front != wgt::StencilStateFaceDescriptor::IGNORE
+                        || state.stencil_back != wgt::StencilStateFaceDescriptor::IGNORE
+                }),
+            )
+            .map_err(pipeline::RenderPipelineError::DepthStencil)?;
+
             if validated_stages.contains(wgt::ShaderStage::FRAGMENT) {
-                for (i, state) in color_states.iter().enumerate() {
-                    let output = &interface[&(i as wgt::ShaderLocation)];
-                    if !validation::check_texture_format(state.format, output) {
-                        log::warn!(
-                            "Incompatible fragment output[{}]. Shader: {:?}. Expected: {:?}",
-                            i,
-                            state.format,
-                            &**output
-                        );
-                        return Err(pipeline::RenderPipelineError::IncompatibleOutputFormat {
-                            index: i as u8,
-                        });
+                let formats = color_states
+                    .iter()
+                    .map(|state| state.format)
+                    .collect::<ArrayVec<[_; MAX_COLOR_TARGETS]>>();
+                validation::check_fragment_outputs(&interface, &formats).map_err(|error| {
+                    match error {
+                        validation::FragmentOutputError::Missing(locations) => {
+                            pipeline::RenderPipelineError::MissingOutputs { locations }
+                        }
+                        validation::FragmentOutputError::IncompatibleFormat {
+                            location,
+                            format,
+                        } => {
+                            log::warn!(
+                                "Incompatible fragment output[{}]. Shader: {:?}. Expected: {:?}",
+                                location,
+                                &*interface[&location],
+                                format
+                            );
+                            pipeline::RenderPipelineError::IncompatibleOutputFormat {
+                                index: location as u8,
+                            }
+                        }
                     }
-                }
+                })?;
             }
 
             let shaders = hal::pso::GraphicsShaderSet {
@@ -2541,6 +2605,12 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                 .iter()
                 .map(|id| &bgl_guard[id.value].entries)
                 .collect::<ArrayVec<[&binding_model::BindEntryMap; MAX_BIND_GROUPS]>>();
+            let stage_env = validation::StageEnvironment {
+                group_layouts: &group_layouts,
+                push_constant_ranges: &layout.push_constant_ranges,
+                features: device.features,
+                limits: device.limits.clone(),
+            };
 
             let interface = validation::StageInterface::default();
             let pipeline_stage = &desc.compute_stage;
@@ -2553,7 +2623,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
             if let Some(ref module) = shader_module.module {
                 let _ = validation::check_stage(
                     module,
-                    &group_layouts,
+                    &stage_env,
                     entry_point_name,
                     ExecutionModel::GLCompute,
                     interface,
@@ -296,3 +296,52 @@ impl Drop for Trace {
         let _ = self.file.write_all(b"]");
     }
 }
+
+#[cfg(all(test, feature = "trace", feature = "replay"))]
+#[test]
+fn test_bind_group_layout_round_trip() {
+    use crate::{binding_model::BindEntryMap, id::TypedId as _};
+
+    let entries = vec![
+        wgt::BindGroupLayoutEntry::new(
+            0,
+            wgt::ShaderStage::VERTEX,
+            wgt::BindingType::UniformBuffer {
+                dynamic: true,
+                min_binding_size: wgt::BufferSize::new(64),
+            },
+        ),
+        wgt::BindGroupLayoutEntry::new(
+            3,
+            wgt::ShaderStage::FRAGMENT,
+            wgt::BindingType::StorageTexture {
+                dimension: wgt::TextureViewDimension::D2Array,
+                format: wgt::TextureFormat::Rgba8Unorm,
+                readonly: true,
+            },
+        ),
+    ];
+    let action = Action::CreateBindGroupLayout {
+        id: id::BindGroupLayoutId::zip(1, 0, wgt::Backend::Empty),
+        label: "layout".to_string(),
+        entries: entries.clone(),
+    };
+    let text = ron::ser::to_string(&action).unwrap();
+    let to_map = |entries: Vec<wgt::BindGroupLayoutEntry>| {
+        entries
+            .into_iter()
+            .map(|entry| (entry.binding, entry))
+            .collect::<BindEntryMap>()
+    };
+    match ron::de::from_str(&text).unwrap() {
+        Action::CreateBindGroupLayout {
+            label,
+            entries: replayed,
+            ..
+        } => {
+            assert_eq!(label, "layout");
+            assert_eq!(to_map(replayed), to_map(entries));
+        }
+        other => panic!("unexpected action: {:?}", other),
+    }
+}
@@ -151,6 +151,26 @@ impl<B: hal::Backend> Adapter<B> {
             wgt::Features::MULTI_DRAW_INDIRECT_COUNT,
             adapter_features.contains(hal::Features::DRAW_INDIRECT_COUNT),
         );
+        features.set(
+            wgt::Features::STORAGE_TEXTURE_READ_WRITE,
+            adapter_features.contains(hal::Features::SHADER_STORAGE_IMAGE_READ_WITHOUT_FORMAT),
+        );
+        features.set(
+            wgt::Features::VERTEX_WRITABLE_STORAGE,
+            adapter_features.contains(hal::Features::VERTEX_STORES_AND_ATOMICS),
+        );
+        features.set(
+            wgt::Features::VERTEX_ATTRIBUTE_64BIT,
+            adapter_features.contains(hal::Features::SHADER_FLOAT64),
+        );
+        // Every backend that has storage texel buffers supports them for `R32Float`.
+        features.set(
+            wgt::Features::TEXEL_BUFFERS,
+            raw.physical_device
+                .format_properties(Some(hal::format::Format::R32Sfloat))
+                .buffer_features
+                .contains(hal::format::BufferFeature::STORAGE_TEXEL),
+        );
 
         let adapter_limits = raw.physical_device.limits();
 
@@ -194,6 +214,15 @@ impl<B: hal::Backend> Adapter<B> {
                 .max(default_limits.max_uniform_buffer_binding_size),
             max_push_constant_size: (adapter_limits.max_push_constants_size as u32)
                 .max(MIN_PUSH_CONSTANT_SIZE), // As an extension, the default is always 0, so define a separate minimum.
+            max_inter_stage_shader_components: (adapter_limits.max_vertex_output_components as u32)
+                .max(default_limits.max_inter_stage_shader_components),
+            // Every location takes up to 4 components.
+            max_inter_stage_shader_variables: ((adapter_limits.max_vertex_output_components / 4)
+                as u32)
+                .max(default_limits.max_inter_stage_shader_variables),
+            max_compute_workgroup_storage_size: (adapter_limits.max_compute_shared_memory_size
+                as u32)
+                .max(default_limits.max_compute_workgroup_storage_size),
         };
 
         Adapter {
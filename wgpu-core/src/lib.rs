@@ -44,6 +44,13 @@ mod track;
 mod validation;
 
 pub use hal::pso::read_spirv;
+pub use validation::{
+    available_entry_points, check_blend_compatibility, check_stage,
+    derive_bind_group_layout_entries, infer_min_binding_sizes, map_vertex_format, max_set_used,
+    to_reflected, type_size, unused_bindings, validate_pipeline_layout_for_module, BlendError,
+    MaybeOwned, ReflectedInterface, ReflectedScalarKind, ReflectedVarying, StageEnvironment,
+    StageError, StageInterface, StageValidationKey, VertexFormatError,
+};
 
 #[cfg(test)]
 use loom::sync::atomic;
@@ -55,6 +62,8 @@ use atomic::{AtomicUsize, Ordering};
 use std::{os::raw::c_char, ptr};
 
 pub const MAX_BIND_GROUPS: usize = 8;
+/// Each shader stage can be served by at most one push constant range.
+pub const MAX_PUSH_CONSTANT_RANGES: usize = device::SHADER_STAGE_COUNT;
 
 type SubmissionIndex = usize;
 type Index = u32;
@@ -5,7 +5,7 @@
 use crate::{
     device::RenderPassContext,
     id::{DeviceId, PipelineLayoutId, ShaderModuleId},
-    validation::StageError,
+    validation::{DepthStencilError, StageError, VertexBufferError, VertexFormatError},
     LifeGuard, RefCount, Stored,
 };
 use std::borrow::Borrow;
@@ -59,6 +59,11 @@ pub enum RenderPipelineError {
         location: wgt::ShaderLocation,
         offset: BufferAddress,
     },
+    VertexBuffer {
+        index: usize,
+        error: VertexBufferError,
+    },
+    VertexFormat(VertexFormatError),
     Stage {
         flag: wgt::ShaderStage,
         error: StageError,
@@ -66,6 +71,10 @@ pub enum RenderPipelineError {
     IncompatibleOutputFormat {
         index: u8,
     },
+    DepthStencil(DepthStencilError),
+    MissingOutputs {
+        locations: Vec<wgt::ShaderLocation>,
+    },
     InvalidSampleCount(u32),
 }
 
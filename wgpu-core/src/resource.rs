@@ -192,6 +192,7 @@ pub struct TextureView<B: hal::Backend> {
     pub(crate) inner: TextureViewInner<B>,
     //TODO: store device_id for quick access?
     pub(crate) format: TextureFormat,
+    pub(crate) dimension: wgt::TextureViewDimension,
     pub(crate) extent: hal::image::Extent,
     pub(crate) samples: hal::image::NumSamples,
     pub(crate) range: hal::image::SubresourceRange,
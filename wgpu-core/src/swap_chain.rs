@@ -150,6 +150,7 @@ impl<G: GlobalIdentityHandlerFactory> Global<G> {
                         },
                     },
                     format: sc.desc.format,
+                    dimension: wgt::TextureViewDimension::D2,
                     extent: hal::image::Extent {
                         width: sc.desc.width,
                         height: sc.desc.height,
@@ -2,11 +2,20 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use crate::{binding_model::BindEntryMap, FastHashMap};
+use crate::{
+    binding_model::{is_storage_format, BindEntryMap, PipelineLayoutError},
+    FastHashMap,
+};
+use smallvec::SmallVec;
 use spirv_headers as spirv;
 use thiserror::Error;
 use wgt::{BindGroupLayoutEntry, BindingType};
 
+use std::{
+    hash::{Hash, Hasher},
+    iter, mem, ops,
+};
+
 #[derive(Clone, Debug, Error)]
 #[error("buffer usage is {actual:?} which does not contain required usage {expected:?}")]
 pub struct MissingBufferUsageError {
@@ -20,8 +29,25 @@ pub fn check_buffer_usage(
     actual: wgt::BufferUsage,
     expected: wgt::BufferUsage,
 ) -> Result<(), MissingBufferUsageError> {
-    if !actual.contains(expected) {
-        Err(MissingBufferUsageError { actual, expected })
+    check_buffer_usage_multi(actual, &[expected])
+}
+
+/// Checks that the given buffer usage contains all of the required buffer
+/// usages, returns an error listing every missing flag otherwise.
+pub fn check_buffer_usage_multi(
+    actual: wgt::BufferUsage,
+    expected: &[wgt::BufferUsage],
+) -> Result<(), MissingBufferUsageError> {
+    let missing = expected
+        .iter()
+        .fold(wgt::BufferUsage::empty(), |missing, &usage| {
+            missing | (usage - actual)
+        });
+    if !missing.is_empty() {
+        Err(MissingBufferUsageError {
+            actual,
+            expected: missing,
+        })
     } else {
         Ok(())
     }
@@ -53,60 +79,162 @@ pub enum BindingError {
     Missing,
     #[error("visibility flags don't include the shader stage")]
     Invisible,
-    #[error("load/store access flags {0:?} don't match the shader")]
-    WrongUsage(naga::GlobalUse),
+    #[error("shader uses {used:?} but the binding only allows {allowed:?}")]
+    WrongUsage {
+        used: naga::GlobalUse,
+        allowed: naga::GlobalUse,
+    },
     #[error("type on the shader side does not match the pipeline binding")]
     WrongType,
     #[error("buffer structure size {0}, added to one element of an unbound array, if it's the last field, ended up greater than the given `min_binding_size`")]
     WrongBufferSize(wgt::BufferAddress),
+    #[error("uniform buffer needs {size} bytes, but the device only allows {limit}")]
+    UniformBufferTooLarge {
+        size: wgt::BufferAddress,
+        limit: wgt::BufferAddress,
+    },
     #[error("view dimension {dim:?} (is array: {is_array}) doesn't match the shader")]
     WrongTextureViewDimension { dim: spirv::Dim, is_array: bool },
-    #[error("component type {0:?} of a sampled texture doesn't match the shader")]
-    WrongTextureComponentType(Option<naga::ScalarKind>),
+    #[error(
+        "sampled texture has {expected:?} components, but the shader samples {actual_kind:?} ones"
+    )]
+    WrongTextureComponentType {
+        expected: wgt::TextureComponentType,
+        actual_kind: Option<naga::ScalarKind>,
+    },
     #[error("texture sampling capability doesn't match the shader")]
     WrongTextureSampled,
     #[error("multisampled flag doesn't match the shader")]
     WrongTextureMultisampled,
+    #[error("multisampled textures can't be arrayed")]
+    MultisampledArrayUnsupported,
     #[error("comparison flag doesn't match the shader")]
     WrongSamplerComparison,
+    #[error("type {0:?} can't be used inside a buffer binding")]
+    UnsupportedType(naga::TypeInner),
+    #[error("buffer size leaves {remainder} bytes past the last whole element of the trailing array with stride {stride}")]
+    PartialArrayElement {
+        stride: wgt::BufferAddress,
+        remainder: wgt::BufferAddress,
+    },
+    #[error("runtime-sized array has elements of zero size")]
+    ZeroStrideRuntimeArray,
+    #[error("array stride {stride} is smaller than the size of its elements, {element_size}")]
+    InvalidArrayStride {
+        stride: wgt::BufferAddress,
+        element_size: wgt::BufferAddress,
+    },
+    #[error("required device feature is missing: {0:?}")]
+    MissingFeature(wgt::Features),
+    #[error("format {0:?} can't be used for storage textures")]
+    UnsupportedStorageTextureFormat(wgt::TextureFormat),
+    #[error("storage texel type {actual:?} doesn't match the texture format, which has texels of {expected:?}")]
+    WrongStorageTexel {
+        expected: naga::TypeInner,
+        actual: naga::TypeInner,
+    },
+    #[error("shader writes to a storage buffer that the layout describes as readonly; mark the binding as not readonly or remove the write")]
+    WriteToReadonlyStorage,
+    #[error("vertex shader writes to storage, which requires the VERTEX_WRITABLE_STORAGE feature")]
+    WritableStorageInVertexStage,
 }
 
 #[derive(Clone, Debug, Error)]
 pub enum InputError {
     #[error("input is not provided by the earlier stage in the pipeline")]
     Missing,
+    #[error("input is not provided by the earlier stage in the pipeline, which only provides locations {available:?}")]
+    UnmatchedLocation { available: Vec<wgt::ShaderLocation> },
     #[error("input type is not compatible with the provided")]
     WrongType,
 }
 
+#[derive(Clone, Debug, Error)]
+pub enum PushConstantError {
+    #[error("no push constant range is visible to the stage")]
+    Invisible,
+    #[error(
+        "push constant block covers bytes {start}..{end}, which is outside of the range {range:?}"
+    )]
+    OutOfRange {
+        start: wgt::BufferAddress,
+        end: wgt::BufferAddress,
+        range: std::ops::Range<u32>,
+    },
+    #[error(transparent)]
+    Type(BindingError),
+}
+
 /// Errors produced when validating a programmable stage of a pipeline.
 #[derive(Clone, Debug, Error)]
 pub enum StageError {
-    #[error("unable to find an entry point matching the {0:?} execution model")]
-    MissingEntryPoint(spirv::ExecutionModel),
-    #[error("error matching global binding at index {binding} in set {set} against the pipeline layout: {error}")]
+    #[error("unable to find an entry point named {name:?} matching the {execution_model:?} execution model, the module provides {available:?}")]
+    MissingEntryPoint {
+        name: String,
+        execution_model: spirv::ExecutionModel,
+        available: Vec<(String, spirv::ExecutionModel)>,
+    },
+    #[error("error matching global binding at index {binding} in set {set} of the {stage:?} stage against the pipeline layout: {error}")]
     Binding {
         set: u32,
         binding: u32,
+        stage: wgt::ShaderStage,
         error: BindingError,
     },
-    #[error(
-        "error matching the stage input at {location} against the previous stage outputs: {error}"
-    )]
+    #[error("error matching the {stage:?} stage input at {location} against the previous stage outputs: {error}")]
     Input {
         location: wgt::ShaderLocation,
+        stage: wgt::ShaderStage,
         error: InputError,
     },
+    #[error("built-in {builtin:?} is not available in the {stage:?} stage")]
+    InvalidBuiltinForStage {
+        builtin: naga::BuiltIn,
+        stage: wgt::ShaderStage,
+    },
+    #[error(
+        "stage outputs {used} inter-stage components, which exceeds the device limit of {limit}"
+    )]
+    TooManyInterStageComponents { used: u32, limit: u32 },
+    #[error(
+        "stage outputs {count} inter-stage variables, which exceeds the device limit of {limit}"
+    )]
+    TooManyInterStageVariables { count: u32, limit: u32 },
+    #[error("error matching the push constant block against the pipeline layout: {error}")]
+    PushConstant { error: PushConstantError },
+    #[error(
+        "shader uses {required} bind groups, but the pipeline layout only declares {provided}"
+    )]
+    TooFewBindGroups { required: usize, provided: usize },
+    #[error("stage writes more than one output to location {location}")]
+    DuplicateOutputLocation { location: wgt::ShaderLocation },
+    #[error("workgroup variables take up {used} bytes, which exceeds the device limit of {limit}")]
+    WorkgroupStorageExceeded {
+        used: wgt::BufferAddress,
+        limit: wgt::BufferAddress,
+    },
+    #[error("unable to determine the size of a workgroup variable: {error}")]
+    InvalidWorkgroupStorage { error: BindingError },
+}
+
+/// Rules a buffer binding lays out its contents with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BufferLayout {
+    /// `std140`, where matrix columns are padded to 16 bytes.
+    Uniform,
+    /// Tightly packed matrix columns.
+    Storage,
 }
 
 fn get_aligned_type_size(
     module: &naga::Module,
     handle: naga::Handle<naga::Type>,
+    layout: BufferLayout,
     allow_unbound: bool,
-) -> wgt::BufferAddress {
+) -> Result<wgt::BufferAddress, BindingError> {
     use naga::TypeInner as Ti;
     //TODO: take alignment into account!
-    match module.types[handle].inner {
+    Ok(match module.types[handle].inner {
         Ti::Scalar { kind: _, width } => width as wgt::BufferAddress / 8,
         Ti::Vector {
             size,
@@ -119,8 +247,12 @@ fn get_aligned_type_size(
             kind: _,
             width,
         } => {
-            rows as wgt::BufferAddress * columns as wgt::BufferAddress * width as wgt::BufferAddress
-                / 8
+            let column_size = rows as wgt::BufferAddress * width as wgt::BufferAddress / 8;
+            let column_stride = match layout {
+                BufferLayout::Uniform => (column_size + 15) & !15,
+                BufferLayout::Storage => column_size,
+            };
+            columns as wgt::BufferAddress * column_stride
         }
         Ti::Pointer { .. } => 4,
         Ti::Array {
@@ -130,7 +262,7 @@ fn get_aligned_type_size(
         } => {
             let base_size = match stride {
                 Some(stride) => stride.get() as wgt::BufferAddress,
-                None => get_aligned_type_size(module, base, false),
+                None => get_aligned_type_size(module, base, layout, false)?,
             };
             base_size * count as wgt::BufferAddress
         }
@@ -138,14 +270,305 @@ fn get_aligned_type_size(
             base,
             size: naga::ArraySize::Dynamic,
             stride,
-        } if allow_unbound => match stride {
-            Some(stride) => stride.get() as wgt::BufferAddress,
-            None => get_aligned_type_size(module, base, false),
+        } if allow_unbound => {
+            let element_size = match stride {
+                Some(stride) => stride.get() as wgt::BufferAddress,
+                None => get_aligned_type_size(module, base, layout, false)?,
+            };
+            if element_size == 0 {
+                return Err(BindingError::ZeroStrideRuntimeArray);
+            }
+            element_size
+        }
+        Ti::Struct { ref members } => match members.last() {
+            Some(member) => {
+                member.offset as wgt::BufferAddress
+                    + get_aligned_type_size(module, member.ty, layout, false)?
+            }
+            None => 0,
+        },
+        ref other => return Err(BindingError::UnsupportedType(other.clone())),
+    })
+}
+
+/// Checks that every array with an explicit stride inside the type has room
+/// for its elements, which a well-formed module always does.
+fn check_array_strides(
+    module: &naga::Module,
+    handle: naga::Handle<naga::Type>,
+    layout: BufferLayout,
+) -> Result<(), BindingError> {
+    match module.types[handle].inner {
+        naga::TypeInner::Array { base, stride, .. } => {
+            check_array_strides(module, base, layout)?;
+            if let Some(stride) = stride {
+                let stride = stride.get() as wgt::BufferAddress;
+                let element_size = get_aligned_type_size(module, base, layout, false)?;
+                if stride < element_size {
+                    return Err(BindingError::InvalidArrayStride {
+                        stride,
+                        element_size,
+                    });
+                }
+            }
+            Ok(())
+        }
+        naga::TypeInner::Struct { ref members } => {
+            for member in members {
+                check_array_strides(module, member.ty, layout)?;
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns the size in bytes of a naga type, as it would be laid out in a
+/// uniform or storage buffer.
+///
+/// Scalars, vectors and matrices are tightly packed, arrays use their
+/// explicit stride when it's given, and structures end where their last
+/// member ends. Alignment padding is not added beyond what the member
+/// offsets and array strides already encode.
+///
+/// A runtime-sized array contributes nothing: for a structure ending with
+/// one, this returns the size of the fixed portion before it.
+///
+/// ```
+/// use wgpu_core::type_size;
+///
+/// let mut module = naga::Module::generate_empty();
+/// let vec4 = module.types.append(naga::Type {
+///     name: None,
+///     inner: naga::TypeInner::Vector {
+///         size: naga::VectorSize::Quad,
+///         kind: naga::ScalarKind::Float,
+///         width: 32,
+///     },
+/// });
+/// let member = |ty, offset| naga::StructMember {
+///     name: None,
+///     binding: None,
+///     ty,
+///     offset,
+/// };
+/// let simple = module.types.append(naga::Type {
+///     name: None,
+///     inner: naga::TypeInner::Struct {
+///         members: vec![member(vec4, 0), member(vec4, 16)],
+///     },
+/// });
+/// assert_eq!(type_size(&module, simple).unwrap(), 32);
+///
+/// let lights = module.types.append(naga::Type {
+///     name: None,
+///     inner: naga::TypeInner::Array {
+///         base: vec4,
+///         size: naga::ArraySize::Dynamic,
+///         stride: None,
+///     },
+/// });
+/// let dynamic = module.types.append(naga::Type {
+///     name: None,
+///     inner: naga::TypeInner::Struct {
+///         members: vec![member(vec4, 0), member(lights, 16)],
+///     },
+/// });
+/// assert_eq!(type_size(&module, dynamic).unwrap(), 16);
+/// ```
+pub fn type_size(
+    module: &naga::Module,
+    handle: naga::Handle<naga::Type>,
+) -> Result<wgt::BufferAddress, BindingError> {
+    let is_runtime_array = |handle: naga::Handle<naga::Type>| match module.types[handle].inner {
+        naga::TypeInner::Array {
+            size: naga::ArraySize::Dynamic,
+            ..
+        } => true,
+        _ => false,
+    };
+    if is_runtime_array(handle) {
+        return Ok(0);
+    }
+    match module.types[handle].inner {
+        naga::TypeInner::Struct { ref members } => match members.last() {
+            Some(last) if is_runtime_array(last.ty) => Ok(last.offset as wgt::BufferAddress),
+            _ => get_aligned_type_size(module, handle, BufferLayout::Storage, false),
         },
-        Ti::Struct { ref members } => members.last().map_or(0, |member| {
-            member.offset as wgt::BufferAddress + get_aligned_type_size(module, member.ty, false)
+        _ => get_aligned_type_size(module, handle, BufferLayout::Storage, false),
+    }
+}
+
+fn hash_binding<H: Hasher>(binding: &Option<naga::Binding>, state: &mut H) {
+    match *binding {
+        None => 0u8.hash(state),
+        Some(naga::Binding::BuiltIn(builtin)) => {
+            1u8.hash(state);
+            (builtin as u32).hash(state);
+        }
+        Some(naga::Binding::Location(location)) => {
+            2u8.hash(state);
+            location.hash(state);
+        }
+        Some(naga::Binding::Descriptor { set, binding }) => {
+            3u8.hash(state);
+            set.hash(state);
+            binding.hash(state);
+        }
+    }
+}
+
+/// Hashes the structure of a type, following handles into the type arena so
+/// that the result doesn't depend on where the types are stored.
+fn hash_type<H: Hasher>(module: &naga::Module, handle: naga::Handle<naga::Type>, state: &mut H) {
+    hash_type_inner(Some(module), &module.types[handle].inner, state)
+}
+
+/// Hashes the structure of a type. Without a module, the types it refers to
+/// are left out, which is only correct for types that don't refer to any.
+fn hash_type_inner<H: Hasher>(
+    module: Option<&naga::Module>,
+    inner: &naga::TypeInner,
+    state: &mut H,
+) {
+    use naga::TypeInner as Ti;
+    let hash_base = |base: naga::Handle<naga::Type>, state: &mut H| {
+        if let Some(module) = module {
+            hash_type(module, base, state);
+        }
+    };
+    match *inner {
+        Ti::Scalar { kind, width } => {
+            "scalar".hash(state);
+            (kind as u8).hash(state);
+            width.hash(state);
+        }
+        Ti::Vector { size, kind, width } => {
+            "vector".hash(state);
+            (size as u8).hash(state);
+            (kind as u8).hash(state);
+            width.hash(state);
+        }
+        Ti::Matrix {
+            columns,
+            rows,
+            kind,
+            width,
+        } => {
+            "matrix".hash(state);
+            (columns as u8).hash(state);
+            (rows as u8).hash(state);
+            (kind as u8).hash(state);
+            width.hash(state);
+        }
+        Ti::Pointer { base, class } => {
+            "pointer".hash(state);
+            (class as u32).hash(state);
+            hash_base(base, state);
+        }
+        Ti::Array { base, size, stride } => {
+            "array".hash(state);
+            let count = match size {
+                naga::ArraySize::Static(count) => Some(count),
+                naga::ArraySize::Dynamic => None,
+            };
+            count.hash(state);
+            stride.hash(state);
+            hash_base(base, state);
+        }
+        Ti::Struct { ref members } => {
+            "struct".hash(state);
+            members.len().hash(state);
+            for member in members {
+                member.offset.hash(state);
+                hash_binding(&member.binding, state);
+                hash_base(member.ty, state);
+            }
+        }
+        Ti::Image { base, dim, flags } => {
+            "image".hash(state);
+            (dim as u32).hash(state);
+            flags.bits().hash(state);
+            hash_base(base, state);
+        }
+        Ti::Sampler { comparison } => {
+            "sampler".hash(state);
+            comparison.hash(state);
+        }
+    }
+}
+
+/// Returns a hash of everything an entry point exposes to the pipeline: its
+/// resource bindings, push constants, built-ins and input/output locations,
+/// together with their types and how they are used.
+///
+/// Names and the order of declarations don't contribute, so recompiling a
+/// shader whose interface stayed the same yields the same fingerprint, and
+/// pipelines built from the old one can be reused.
+pub fn interface_fingerprint(
+    module: &naga::Module,
+    entry_point_name: &str,
+    execution_model: spirv::ExecutionModel,
+) -> Result<u64, StageError> {
+    let entry_point = find_entry_point(module, entry_point_name, execution_model)?;
+    let function = &module.functions[entry_point.function];
+    let mut globals = module
+        .global_variables
+        .iter()
+        .zip(&function.global_usage)
+        .filter(|(_, usage)| !usage.is_empty())
+        .map(|((_, var), usage)| {
+            let mut hasher = fxhash::FxHasher::default();
+            hash_binding(&var.binding, &mut hasher);
+            (var.class as u32).hash(&mut hasher);
+            usage.bits().hash(&mut hasher);
+            hash_type(module, var.ty, &mut hasher);
+            hasher.finish()
+        })
+        .collect::<Vec<_>>();
+    globals.sort_unstable();
+
+    let mut hasher = fxhash::FxHasher::default();
+    (execution_model as u32).hash(&mut hasher);
+    globals.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+/// Returns the size of a buffer structure, which ends where its last member
+/// ends, like in `get_aligned_type_size`. A trailing runtime-sized array
+/// counts as a single element.
+fn get_struct_size(
+    module: &naga::Module,
+    members: &[naga::StructMember],
+    layout: BufferLayout,
+) -> Result<wgt::BufferAddress, BindingError> {
+    Ok(match members.last() {
+        Some(last) => {
+            last.offset as wgt::BufferAddress
+                + get_aligned_type_size(module, last.ty, layout, true)?
+        }
+        None => 0,
+    })
+}
+
+/// Checks that the texel type a shader declares for a storage image matches
+/// the format of the binding.
+fn check_storage_texel(
+    module: &naga::Module,
+    base: naga::Handle<naga::Type>,
+    format: wgt::TextureFormat,
+) -> Result<(), BindingError> {
+    if !is_storage_format(format) {
+        return Err(BindingError::UnsupportedStorageTextureFormat(format));
+    }
+    let expected = map_texture_format(format);
+    let actual = &module.types[base].inner;
+    match (texel_components(&expected), texel_components(actual)) {
+        (Some(required), Some(provided)) if required == provided => Ok(()),
+        _ => Err(BindingError::WrongStorageTexel {
+            expected,
+            actual: actual.clone(),
         }),
-        _ => panic!("Unexpected struct field"),
     }
 }
 
@@ -154,6 +577,9 @@ fn check_binding(
     var: &naga::GlobalVariable,
     entry: &BindGroupLayoutEntry,
     usage: naga::GlobalUse,
+    stage: wgt::ShaderStage,
+    features: wgt::Features,
+    limits: &wgt::Limits,
 ) -> Result<(), BindingError> {
     let mut ty_inner = &module.types[var.ty].inner;
     //TODO: change naga's IR to avoid a pointer here
@@ -162,11 +588,15 @@ fn check_binding(
     }
     let allowed_usage = match *ty_inner {
         naga::TypeInner::Struct { ref members } => {
-            let (allowed_usage, min_size) = match entry.ty {
+            let (allowed_usage, min_size, layout) = match entry.ty {
                 BindingType::UniformBuffer {
                     dynamic: _,
                     min_binding_size,
-                } => (naga::GlobalUse::LOAD, min_binding_size),
+                } => (
+                    naga::GlobalUse::LOAD,
+                    min_binding_size,
+                    BufferLayout::Uniform,
+                ),
                 BindingType::StorageBuffer {
                     dynamic: _,
                     min_binding_size,
@@ -177,13 +607,20 @@ fn check_binding(
                     } else {
                         naga::GlobalUse::all()
                     };
-                    (global_use, min_binding_size)
+                    (global_use, min_binding_size, BufferLayout::Storage)
                 }
                 _ => return Err(BindingError::WrongType),
             };
-            let mut actual_size = 0;
-            for (i, member) in members.iter().enumerate() {
-                actual_size += get_aligned_type_size(module, member.ty, i + 1 == members.len());
+            for member in members {
+                check_array_strides(module, member.ty, layout)?;
+            }
+            let actual_size = get_struct_size(module, members, layout)?;
+            let limit = limits.max_uniform_buffer_binding_size as wgt::BufferAddress;
+            if layout == BufferLayout::Uniform && actual_size > limit {
+                return Err(BindingError::UniformBufferTooLarge {
+                    size: actual_size,
+                    limit,
+                });
             }
             match min_size {
                 Some(non_zero) if non_zero.get() < actual_size => {
@@ -191,6 +628,21 @@ fn check_binding(
                 }
                 _ => (),
             }
+            // The size past the fixed part has to hold whole elements
+            // of the trailing runtime-sized array.
+            if let (Some(min_size), Some(last)) = (min_size, members.last()) {
+                if let naga::TypeInner::Array {
+                    size: naga::ArraySize::Dynamic,
+                    ..
+                } = module.types[last.ty].inner
+                {
+                    let stride = get_aligned_type_size(module, last.ty, layout, true)?;
+                    let remainder = (min_size.get() - last.offset as wgt::BufferAddress) % stride;
+                    if remainder != 0 {
+                        return Err(BindingError::PartialArrayElement { stride, remainder });
+                    }
+                }
+            }
             allowed_usage
         }
         naga::TypeInner::Sampler { comparison } => match entry.ty {
@@ -203,7 +655,37 @@ fn check_binding(
             }
             _ => return Err(BindingError::WrongType),
         },
+        naga::TypeInner::Image {
+            base,
+            dim: spirv::Dim::DimBuffer,
+            flags,
+        } => {
+            let (format, readonly) = match entry.ty {
+                BindingType::StorageTexelBuffer { format, readonly } => (format, readonly),
+                _ => {
+                    return Err(BindingError::WrongTextureViewDimension {
+                        dim: spirv::Dim::DimBuffer,
+                        is_array: flags.contains(naga::ImageFlags::ARRAYED),
+                    })
+                }
+            };
+            if !features.contains(wgt::Features::TEXEL_BUFFERS) {
+                return Err(BindingError::MissingFeature(wgt::Features::TEXEL_BUFFERS));
+            }
+            if flags.contains(naga::ImageFlags::SAMPLED) {
+                return Err(BindingError::WrongTextureSampled);
+            }
+            check_storage_texel(module, base, format)?;
+            if readonly {
+                naga::GlobalUse::LOAD
+            } else {
+                naga::GlobalUse::LOAD | naga::GlobalUse::STORE
+            }
+        }
         naga::TypeInner::Image { base, dim, flags } => {
+            if flags.contains(naga::ImageFlags::MULTISAMPLED | naga::ImageFlags::ARRAYED) {
+                return Err(BindingError::MultisampledArrayUnsupported);
+            }
             if flags.contains(naga::ImageFlags::MULTISAMPLED) {
                 match entry.ty {
                     BindingType::SampledTexture {
@@ -260,18 +742,34 @@ fn check_binding(
                             if kind == expected_scalar_kind => {}
                         naga::TypeInner::Scalar { kind, .. }
                         | naga::TypeInner::Vector { kind, .. } => {
-                            return Err(BindingError::WrongTextureComponentType(Some(kind)))
+                            return Err(BindingError::WrongTextureComponentType {
+                                expected: component_type,
+                                actual_kind: Some(kind),
+                            })
+                        }
+                        _ => {
+                            return Err(BindingError::WrongTextureComponentType {
+                                expected: component_type,
+                                actual_kind: None,
+                            })
                         }
-                        _ => return Err(BindingError::WrongTextureComponentType(None)),
                     };
                     (naga::GlobalUse::LOAD, true)
                 }
-                BindingType::StorageTexture { readonly, .. } => {
+                BindingType::StorageTexture {
+                    readonly, format, ..
+                } => {
+                    check_storage_texel(module, base, format)?;
                     if readonly {
-                        //TODO: check entry.storage_texture_format
                         (naga::GlobalUse::LOAD, false)
-                    } else {
+                    } else if !usage.contains(naga::GlobalUse::LOAD) {
                         (naga::GlobalUse::STORE, false)
+                    } else if features.contains(wgt::Features::STORAGE_TEXTURE_READ_WRITE) {
+                        (naga::GlobalUse::LOAD | naga::GlobalUse::STORE, false)
+                    } else {
+                        return Err(BindingError::MissingFeature(
+                            wgt::Features::STORAGE_TEXTURE_READ_WRITE,
+                        ));
                     }
                 }
                 _ => return Err(BindingError::WrongType),
@@ -283,14 +781,70 @@ fn check_binding(
         }
         _ => return Err(BindingError::WrongType),
     };
-    if allowed_usage.contains(usage) {
-        Ok(())
-    } else {
-        Err(BindingError::WrongUsage(usage))
+    if !allowed_usage.contains(usage) {
+        return Err(match entry.ty {
+            BindingType::StorageBuffer { readonly: true, .. }
+                if usage.contains(naga::GlobalUse::STORE) =>
+            {
+                BindingError::WriteToReadonlyStorage
+            }
+            _ => BindingError::WrongUsage {
+                used: usage,
+                allowed: allowed_usage,
+            },
+        });
+    }
+    // Writable storage is only available to the vertex stage on request.
+    if stage == wgt::ShaderStage::VERTEX
+        && usage.contains(naga::GlobalUse::STORE)
+        && !features.contains(wgt::Features::VERTEX_WRITABLE_STORAGE)
+    {
+        match entry.ty {
+            BindingType::StorageBuffer { .. }
+            | BindingType::StorageTexture { .. }
+            | BindingType::StorageTexelBuffer { .. } => {
+                return Err(BindingError::WritableStorageInVertexStage)
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// How the component width of a provided type may differ from the required one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum WidthRule {
+    /// The provided components may be wider, e.g. a fragment output that is
+    /// written into a narrower render target.
+    Narrow,
+    /// The provided components may be narrower, since vertex fetch expands
+    /// attributes, e.g. halves into floats.
+    Expand,
+    /// The widths have to match, e.g. for varyings passed between stages.
+    Exact,
+}
+
+impl WidthRule {
+    fn allows(self, required: naga::Bytes, provided: naga::Bytes) -> bool {
+        match self {
+            WidthRule::Narrow => required <= provided,
+            WidthRule::Expand => required >= provided,
+            WidthRule::Exact => required == provided,
+        }
     }
 }
 
-fn is_sub_type(sub: &naga::TypeInner, provided: &naga::TypeInner) -> bool {
+/// Checks that the `provided` type can be used where `sub` is expected.
+///
+/// Struct members are types of the module that declares the struct, so
+/// structs are only compared member by member when `modules` gives the ones
+/// `sub` and `provided` come from, and have to be equal otherwise.
+fn is_sub_type(
+    sub: &naga::TypeInner,
+    provided: &naga::TypeInner,
+    modules: Option<(&naga::Module, &naga::Module)>,
+    widths: WidthRule,
+) -> bool {
     use naga::TypeInner as Ti;
 
     match (sub, provided) {
@@ -303,7 +857,7 @@ fn is_sub_type(sub: &naga::TypeInner, provided: &naga::TypeInner) -> bool {
                 kind: k1,
                 width: w1,
             },
-        ) => k0 == k1 && w0 <= w1,
+        ) => k0 == k1 && widths.allows(w0, w1),
         (
             &Ti::Scalar {
                 kind: k0,
@@ -314,7 +868,7 @@ fn is_sub_type(sub: &naga::TypeInner, provided: &naga::TypeInner) -> bool {
                 kind: k1,
                 width: w1,
             },
-        ) => k0 == k1 && w0 <= w1,
+        ) => k0 == k1 && widths.allows(w0, w1),
         (
             &Ti::Vector {
                 size: s0,
@@ -326,7 +880,7 @@ fn is_sub_type(sub: &naga::TypeInner, provided: &naga::TypeInner) -> bool {
                 kind: k1,
                 width: w1,
             },
-        ) => s0 as u8 <= s1 as u8 && k0 == k1 && w0 <= w1,
+        ) => s0 as u8 <= s1 as u8 && k0 == k1 && widths.allows(w0, w1),
         (
             &Ti::Matrix {
                 columns: c0,
@@ -340,8 +894,21 @@ fn is_sub_type(sub: &naga::TypeInner, provided: &naga::TypeInner) -> bool {
                 kind: k1,
                 width: w1,
             },
-        ) => c0 == c1 && r0 == r1 && k0 == k1 && w0 <= w1,
-        (&Ti::Struct { members: ref m0 }, &Ti::Struct { members: ref m1 }) => m0 == m1,
+        ) => c0 == c1 && r0 == r1 && k0 == k1 && w0 == w1,
+        (&Ti::Struct { members: ref m0 }, &Ti::Struct { members: ref m1 }) => match modules {
+            Some((sub_module, provided_module)) => {
+                m0.len() == m1.len()
+                    && m0.iter().zip(m1).all(|(a, b)| {
+                        is_sub_type(
+                            &sub_module.types[a.ty].inner,
+                            &provided_module.types[b.ty].inner,
+                            modules,
+                            widths,
+                        )
+                    })
+            }
+            None => m0 == m1,
+        },
         _ => false,
     }
 }
@@ -361,7 +928,60 @@ impl<'a, T> std::ops::Deref for MaybeOwned<'a, T> {
     }
 }
 
-pub fn map_vertex_format(format: wgt::VertexFormat) -> naga::TypeInner {
+/// Cloning always produces an owned value, so the clone doesn't keep the
+/// source of a borrowed value alive.
+impl<'a, T: Clone> Clone for MaybeOwned<'a, T> {
+    fn clone(&self) -> Self {
+        MaybeOwned::Owned(T::clone(self))
+    }
+}
+
+impl<'a, T: Clone> MaybeOwned<'a, T> {
+    pub fn into_owned(self) -> T {
+        match self {
+            MaybeOwned::Owned(value) => value,
+            MaybeOwned::Borrowed(value) => value.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+pub enum VertexFormatError {
+    #[error("vertex format {format:?} requires device feature {feature:?}")]
+    MissingFeature {
+        format: wgt::VertexFormat,
+        feature: wgt::Features,
+    },
+}
+
+/// Returns the device features a vertex format can only be used with.
+fn vertex_format_features(format: wgt::VertexFormat) -> wgt::Features {
+    use wgt::VertexFormat as Vf;
+    match format {
+        Vf::Double | Vf::Double2 | Vf::Double3 | Vf::Double4 => {
+            wgt::Features::VERTEX_ATTRIBUTE_64BIT
+        }
+        _ => wgt::Features::empty(),
+    }
+}
+
+/// Returns the type a shader sees for a vertex format, checking that the
+/// features it needs are enabled.
+pub fn map_vertex_format(
+    format: wgt::VertexFormat,
+    features: wgt::Features,
+) -> Result<naga::TypeInner, VertexFormatError> {
+    let required = vertex_format_features(format);
+    if !features.contains(required) {
+        return Err(VertexFormatError::MissingFeature {
+            format,
+            feature: required - features,
+        });
+    }
+    Ok(vertex_format_type(format))
+}
+
+fn vertex_format_type(format: wgt::VertexFormat) -> naga::TypeInner {
     use naga::TypeInner as Ti;
     use wgt::VertexFormat as Vf;
     match format {
@@ -492,9 +1112,107 @@ pub fn map_vertex_format(format: wgt::VertexFormat) -> naga::TypeInner {
             kind: naga::ScalarKind::Sint,
             width: 32,
         },
+        Vf::Double => Ti::Scalar {
+            kind: naga::ScalarKind::Float,
+            width: 64,
+        },
+        Vf::Double2 => Ti::Vector {
+            size: naga::VectorSize::Bi,
+            kind: naga::ScalarKind::Float,
+            width: 64,
+        },
+        Vf::Double3 => Ti::Vector {
+            size: naga::VectorSize::Tri,
+            kind: naga::ScalarKind::Float,
+            width: 64,
+        },
+        Vf::Double4 => Ti::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Float,
+            width: 64,
+        },
+    }
+}
+
+/// Vertex attribute offsets need to be aligned to this many bytes,
+/// or to the size of the attribute if it's smaller.
+const VERTEX_ATTRIBUTE_ALIGNMENT: wgt::BufferAddress = 4;
+
+#[derive(Clone, Debug, Error)]
+pub enum VertexBufferError {
+    #[error("attribute at location {location} has offset {offset}, which is not a multiple of {alignment}")]
+    MisalignedOffset {
+        location: wgt::ShaderLocation,
+        offset: wgt::BufferAddress,
+        alignment: wgt::BufferAddress,
+    },
+    #[error(
+        "attribute at location {location} ends at byte {end}, past the buffer stride of {stride}"
+    )]
+    AttributeOverrun {
+        location: wgt::ShaderLocation,
+        end: wgt::BufferAddress,
+        stride: wgt::BufferAddress,
+    },
+    #[error("attributes at locations {first} and {second} overlap")]
+    OverlappingAttributes {
+        first: wgt::ShaderLocation,
+        second: wgt::ShaderLocation,
+    },
+}
+
+/// Checks that the attributes of a vertex buffer are aligned, fit within the
+/// stride of the buffer, and don't overlap with each other.
+///
+/// A zero stride is allowed, in which case every vertex reads the same data.
+pub fn check_vertex_buffer_layout(
+    layout: &wgt::VertexBufferDescriptor,
+) -> Result<(), VertexBufferError> {
+    for (i, attribute) in layout.attributes.iter().enumerate() {
+        let alignment = attribute.format.size().min(VERTEX_ATTRIBUTE_ALIGNMENT);
+        if attribute.offset % alignment != 0 {
+            return Err(VertexBufferError::MisalignedOffset {
+                location: attribute.shader_location,
+                offset: attribute.offset,
+                alignment,
+            });
+        }
+        let end = attribute.offset + attribute.format.size();
+        if layout.stride != 0 && end > layout.stride {
+            return Err(VertexBufferError::AttributeOverrun {
+                location: attribute.shader_location,
+                end,
+                stride: layout.stride,
+            });
+        }
+        for other in &layout.attributes[..i] {
+            let other_end = other.offset + other.format.size();
+            if attribute.offset < other_end && other.offset < end {
+                return Err(VertexBufferError::OverlappingAttributes {
+                    first: other.shader_location,
+                    second: attribute.shader_location,
+                });
+            }
+        }
     }
+    Ok(())
 }
 
+/// Returns the number of components and their kind for a texel type.
+fn texel_components(ty: &naga::TypeInner) -> Option<(u8, naga::ScalarKind)> {
+    match *ty {
+        naga::TypeInner::Scalar { kind, .. } => Some((1, kind)),
+        naga::TypeInner::Vector { size, kind, .. } => Some((size as u8, kind)),
+        _ => None,
+    }
+}
+
+/// Returns the texel type a shader sees for a texture format.
+///
+/// Block-compressed formats aren't part of `wgt::TextureFormat` yet. Unlike
+/// packed formats such as `Rgb9e5Ufloat`, which have one texel per block,
+/// they need copies in `command::transfer` to be validated in whole blocks
+/// first, so they're left out here as well.
 fn map_texture_format(format: wgt::TextureFormat) -> naga::TypeInner {
     use naga::{ScalarKind as Sk, TypeInner as Ti, VectorSize as Vs};
     use wgt::TextureFormat as Tf;
@@ -595,6 +1313,12 @@ fn map_texture_format(format: wgt::TextureFormat) -> naga::TypeInner {
             kind: Sk::Float,
             width: 11,
         },
+        // Shared exponent formats are always sampled as full floats.
+        Tf::Rgb9e5Ufloat => Ti::Vector {
+            size: Vs::Tri,
+            kind: Sk::Float,
+            width: 32,
+        },
         Tf::Rg32Uint => Ti::Vector {
             size: Vs::Bi,
             kind: Sk::Uint,
@@ -646,47 +1370,679 @@ fn map_texture_format(format: wgt::TextureFormat) -> naga::TypeInner {
     }
 }
 
+#[derive(Clone, Debug, Error)]
+pub enum BlendError {
+    #[error(
+        "blend factor {factor:?} reads the destination alpha, but the target format {format:?} has no alpha channel"
+    )]
+    AlphaFactorWithoutAlpha {
+        format: wgt::TextureFormat,
+        factor: wgt::BlendFactor,
+    },
+}
+
+fn has_alpha_channel(format: wgt::TextureFormat) -> bool {
+    match format {
+        wgt::TextureFormat::Depth32Float
+        | wgt::TextureFormat::Depth24Plus
+        | wgt::TextureFormat::Depth24PlusStencil8 => false,
+        _ => match map_texture_format(format) {
+            naga::TypeInner::Vector {
+                size: naga::VectorSize::Quad,
+                ..
+            } => true,
+            _ => false,
+        },
+    }
+}
+
+/// Checks that a blend state doesn't read the destination alpha of a target
+/// that has no alpha channel.
+///
+/// Source alpha factors are fine on any target, since the source alpha comes
+/// from the shader output rather than from the target.
+pub fn check_blend_compatibility(
+    format: wgt::TextureFormat,
+    blend: &wgt::BlendDescriptor,
+) -> Result<(), BlendError> {
+    use wgt::BlendFactor as Bf;
+    if has_alpha_channel(format) {
+        return Ok(());
+    }
+    for &factor in [blend.src_factor, blend.dst_factor].iter() {
+        match factor {
+            Bf::DstAlpha | Bf::OneMinusDstAlpha => {
+                return Err(BlendError::AlphaFactorWithoutAlpha { format, factor })
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug, Error)]
+pub enum DepthStencilError {
+    #[error("format {0:?} can't be used for a depth/stencil attachment")]
+    NotDepthStencilFormat(wgt::TextureFormat),
+    #[error("fragment shader writes depth, but the pipeline has no depth attachment")]
+    MissingDepthAttachment,
+    #[error("stencil operations are used, but the format {0:?} has no stencil aspect")]
+    MissingStencilAspect(wgt::TextureFormat),
+}
+
+/// Checks the depth/stencil attachment format of a pipeline against the way
+/// it's used: a fragment shader writing depth needs a depth attachment, and
+/// stencil operations need a format with a stencil aspect.
+pub fn check_depth_stencil_format(
+    format: Option<wgt::TextureFormat>,
+    shader_writes_depth: bool,
+    uses_stencil: bool,
+) -> Result<(), DepthStencilError> {
+    use wgt::TextureFormat as Tf;
+    let has_stencil = match format {
+        None if shader_writes_depth => return Err(DepthStencilError::MissingDepthAttachment),
+        None => return Ok(()),
+        Some(Tf::Depth32Float) | Some(Tf::Depth24Plus) => false,
+        Some(Tf::Depth24PlusStencil8) => true,
+        Some(other) => return Err(DepthStencilError::NotDepthStencilFormat(other)),
+    };
+    match format {
+        Some(format) if uses_stencil && !has_stencil => {
+            Err(DepthStencilError::MissingStencilAspect(format))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns true if the fragment entry point writes the `FragDepth` built-in.
+pub fn writes_frag_depth(
+    module: &naga::Module,
+    entry_point_name: &str,
+) -> Result<bool, StageError> {
+    let entry_point = find_entry_point(module, entry_point_name, spirv::ExecutionModel::Fragment)?;
+    let function = &module.functions[entry_point.function];
+    Ok(module
+        .global_variables
+        .iter()
+        .zip(&function.global_usage)
+        .any(|((_, var), usage)| match var.binding {
+            Some(naga::Binding::BuiltIn(naga::BuiltIn::FragDepth)) => {
+                usage.contains(naga::GlobalUse::STORE)
+            }
+            _ => false,
+        }))
+}
+
 /// Return true if the fragment `format` is covered by the provided `output`.
 pub fn check_texture_format(format: wgt::TextureFormat, output: &naga::TypeInner) -> bool {
     let required = map_texture_format(format);
-    is_sub_type(&required, output)
+    match format {
+        // Packed formats are always written from full float vectors,
+        // so the component width of the output doesn't matter.
+        wgt::TextureFormat::Rgb10a2Unorm | wgt::TextureFormat::Rg11b10Float => {
+            match (required, output) {
+                (
+                    naga::TypeInner::Vector { size: s0, .. },
+                    &naga::TypeInner::Vector {
+                        size: s1,
+                        kind: naga::ScalarKind::Float,
+                        width: _,
+                    },
+                ) => s0 as u8 <= s1 as u8,
+                _ => false,
+            }
+        }
+        _ => is_sub_type(&required, output, None, WidthRule::Narrow),
+    }
 }
 
-pub type StageInterface<'a> = FastHashMap<wgt::ShaderLocation, MaybeOwned<'a, naga::TypeInner>>;
+#[derive(Clone, Debug, Error)]
+pub enum FragmentOutputError {
+    #[error("fragment shader doesn't write the outputs at locations {0:?}")]
+    Missing(Vec<wgt::ShaderLocation>),
+    #[error("fragment output at location {location} is not compatible with the target format {format:?}")]
+    IncompatibleFormat {
+        location: wgt::ShaderLocation,
+        format: wgt::TextureFormat,
+    },
+}
 
-pub fn check_stage<'a>(
-    module: &'a naga::Module,
-    group_layouts: &[&BindEntryMap],
+/// Check that the fragment `outputs` cover each of the color target formats,
+/// where the target at index `i` is fed by the output at location `i`.
+pub fn check_fragment_outputs(
+    outputs: &StageInterface,
+    expected_formats: &[wgt::TextureFormat],
+) -> Result<(), FragmentOutputError> {
+    let mut missing = Vec::new();
+    for (i, &format) in expected_formats.iter().enumerate() {
+        let location = i as wgt::ShaderLocation;
+        match outputs.get(&location) {
+            Some(output) => {
+                if !check_texture_format(format, output) {
+                    return Err(FragmentOutputError::IncompatibleFormat { location, format });
+                }
+            }
+            None => missing.push(location),
+        }
+    }
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(FragmentOutputError::Missing(missing))
+    }
+}
+
+/// Return the shader stages that are allowed to use the `builtin`.
+fn builtin_stages(builtin: naga::BuiltIn) -> wgt::ShaderStage {
+    use naga::BuiltIn as Bi;
+    match builtin {
+        Bi::BaseInstance
+        | Bi::BaseVertex
+        | Bi::InstanceIndex
+        | Bi::VertexIndex
+        | Bi::Position
+        | Bi::PointSize => wgt::ShaderStage::VERTEX,
+        Bi::ClipDistance => wgt::ShaderStage::VERTEX | wgt::ShaderStage::FRAGMENT,
+        Bi::FragCoord | Bi::FrontFacing | Bi::SampleIndex | Bi::FragDepth => {
+            wgt::ShaderStage::FRAGMENT
+        }
+        Bi::GlobalInvocationId
+        | Bi::LocalInvocationId
+        | Bi::LocalInvocationIndex
+        | Bi::WorkGroupId => wgt::ShaderStage::COMPUTE,
+    }
+}
+
+fn map_execution_model(execution_model: spirv::ExecutionModel) -> wgt::ShaderStage {
+    match execution_model {
+        spirv::ExecutionModel::Vertex => wgt::ShaderStage::VERTEX,
+        spirv::ExecutionModel::Fragment => wgt::ShaderStage::FRAGMENT,
+        spirv::ExecutionModel::GLCompute => wgt::ShaderStage::COMPUTE,
+        // the entry point wouldn't match otherwise
+        _ => unreachable!(),
+    }
+}
+
+/// Number of locations a stage interface can hold before it has to allocate.
+const STAGE_INTERFACE_INLINE_LOCATIONS: usize = 8;
+
+/// Types of the values passed between shader stages, keyed by location.
+///
+/// Stages rarely exchange more than a handful of values, so the entries are
+/// stored inline and looked up linearly instead of going through a hash map.
+#[derive(Default)]
+pub struct StageInterface<'a> {
+    entries: SmallVec<
+        [(wgt::ShaderLocation, MaybeOwned<'a, naga::TypeInner>); STAGE_INTERFACE_INLINE_LOCATIONS],
+    >,
+    /// Module the borrowed types come from, if they are shader outputs.
+    module: Option<&'a naga::Module>,
+}
+
+impl<'a> StageInterface<'a> {
+    /// Sets the type at `location`, returning the previous one if there was any.
+    pub fn insert(
+        &mut self,
+        location: wgt::ShaderLocation,
+        ty: MaybeOwned<'a, naga::TypeInner>,
+    ) -> Option<MaybeOwned<'a, naga::TypeInner>> {
+        match self.entries.iter_mut().find(|(loc, _)| *loc == location) {
+            Some((_, old)) => Some(mem::replace(old, ty)),
+            None => {
+                self.entries.push((location, ty));
+                None
+            }
+        }
+    }
+
+    pub fn get(&self, location: &wgt::ShaderLocation) -> Option<&MaybeOwned<'a, naga::TypeInner>> {
+        self.entries
+            .iter()
+            .find(|(loc, _)| loc == location)
+            .map(|(_, ty)| ty)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the module the types of the interface belong to, if any.
+    pub fn module(&self) -> Option<&'a naga::Module> {
+        self.module
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = (&wgt::ShaderLocation, &MaybeOwned<'a, naga::TypeInner>)> {
+        self.entries.iter().map(|(loc, ty)| (loc, ty))
+    }
+}
+
+impl<'a> ops::Index<&wgt::ShaderLocation> for StageInterface<'a> {
+    type Output = MaybeOwned<'a, naga::TypeInner>;
+
+    fn index(&self, location: &wgt::ShaderLocation) -> &Self::Output {
+        self.get(location)
+            .unwrap_or_else(|| panic!("no stage interface entry at location {}", location))
+    }
+}
+
+impl<'a> iter::FromIterator<(wgt::ShaderLocation, MaybeOwned<'a, naga::TypeInner>)>
+    for StageInterface<'a>
+{
+    fn from_iter<I: IntoIterator<Item = (wgt::ShaderLocation, MaybeOwned<'a, naga::TypeInner>)>>(
+        iter: I,
+    ) -> Self {
+        let mut interface = Self::default();
+        for (location, ty) in iter {
+            interface.insert(location, ty);
+        }
+        interface
+    }
+}
+
+/// Scalar kind of a reflected stage interface entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub enum ReflectedScalarKind {
+    Sint,
+    Uint,
+    Float,
+    Bool,
+}
+
+/// Owned description of the type passed at one stage interface location.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct ReflectedVarying {
+    pub kind: ReflectedScalarKind,
+    pub width: u8,
+    /// Number of components in a vector, or rows in a matrix column.
+    pub components: u8,
+    /// Number of matrix columns, 1 for scalars and vectors.
+    pub columns: u8,
+}
+
+/// A stage interface that doesn't borrow from the naga module it was
+/// produced from, so it can be stored and used to validate the next stage
+/// after that module is gone.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "trace", derive(serde::Serialize))]
+#[cfg_attr(feature = "replay", derive(serde::Deserialize))]
+pub struct ReflectedInterface {
+    pub varyings: Vec<(wgt::ShaderLocation, ReflectedVarying)>,
+}
+
+fn unreflect_vector_size(components: u8) -> Option<naga::VectorSize> {
+    match components {
+        2 => Some(naga::VectorSize::Bi),
+        3 => Some(naga::VectorSize::Tri),
+        4 => Some(naga::VectorSize::Quad),
+        _ => None,
+    }
+}
+
+fn reflect_varying(ty: &naga::TypeInner) -> Option<ReflectedVarying> {
+    let reflect_kind = |kind| match kind {
+        naga::ScalarKind::Sint => ReflectedScalarKind::Sint,
+        naga::ScalarKind::Uint => ReflectedScalarKind::Uint,
+        naga::ScalarKind::Float => ReflectedScalarKind::Float,
+        naga::ScalarKind::Bool => ReflectedScalarKind::Bool,
+    };
+    Some(match *ty {
+        naga::TypeInner::Scalar { kind, width } => ReflectedVarying {
+            kind: reflect_kind(kind),
+            width,
+            components: 1,
+            columns: 1,
+        },
+        naga::TypeInner::Vector { size, kind, width } => ReflectedVarying {
+            kind: reflect_kind(kind),
+            width,
+            components: size as u8,
+            columns: 1,
+        },
+        naga::TypeInner::Matrix {
+            columns,
+            rows,
+            kind,
+            width,
+        } => ReflectedVarying {
+            kind: reflect_kind(kind),
+            width,
+            components: rows as u8,
+            columns: columns as u8,
+        },
+        _ => return None,
+    })
+}
+
+/// Returns the number of components a varying of the given type takes up,
+/// counting every member of a structure and every element of an array.
+fn varying_components(module: &naga::Module, ty: &naga::TypeInner) -> u32 {
+    match *ty {
+        naga::TypeInner::Pointer { base, .. } => {
+            varying_components(module, &module.types[base].inner)
+        }
+        naga::TypeInner::Array {
+            base,
+            size: naga::ArraySize::Static(count),
+            ..
+        } => count * varying_components(module, &module.types[base].inner),
+        naga::TypeInner::Struct { ref members } => members
+            .iter()
+            .map(|member| varying_components(module, &module.types[member.ty].inner))
+            .sum(),
+        ref other => reflect_varying(other).map_or(0, |varying| {
+            varying.components as u32 * varying.columns as u32
+        }),
+    }
+}
+
+impl ReflectedVarying {
+    fn to_type(self) -> Option<naga::TypeInner> {
+        let kind = match self.kind {
+            ReflectedScalarKind::Sint => naga::ScalarKind::Sint,
+            ReflectedScalarKind::Uint => naga::ScalarKind::Uint,
+            ReflectedScalarKind::Float => naga::ScalarKind::Float,
+            ReflectedScalarKind::Bool => naga::ScalarKind::Bool,
+        };
+        let width = self.width;
+        Some(match (self.columns, self.components) {
+            (1, 1) => naga::TypeInner::Scalar { kind, width },
+            (1, components) => naga::TypeInner::Vector {
+                size: unreflect_vector_size(components)?,
+                kind,
+                width,
+            },
+            (columns, rows) => naga::TypeInner::Matrix {
+                columns: unreflect_vector_size(columns)?,
+                rows: unreflect_vector_size(rows)?,
+                kind,
+                width,
+            },
+        })
+    }
+}
+
+impl ReflectedInterface {
+    /// Rebuilds a stage interface that the next stage can be checked against.
+    ///
+    /// Entries with a component or column count naga can't represent are
+    /// left out, and show up as missing inputs.
+    pub fn to_stage_interface(&self) -> StageInterface<'static> {
+        self.varyings
+            .iter()
+            .filter_map(|&(location, varying)| {
+                varying
+                    .to_type()
+                    .map(|ty| (location, MaybeOwned::Owned(ty)))
+            })
+            .collect()
+    }
+}
+
+/// Copies a stage interface into an owned form that outlives the module.
+///
+/// Only scalars, vectors and matrices can be passed between stages, so
+/// entries of other types are left out.
+pub fn to_reflected(interface: &StageInterface) -> ReflectedInterface {
+    let mut varyings = interface
+        .iter()
+        .filter_map(|(&location, ty)| reflect_varying(ty).map(|varying| (location, varying)))
+        .collect::<Vec<_>>();
+    varyings.sort_by_key(|&(location, _)| location);
+    ReflectedInterface { varyings }
+}
+
+fn check_push_constant(
+    module: &naga::Module,
+    var: &naga::GlobalVariable,
+    ranges: &[wgt::PushConstantRange],
+    stage_bit: wgt::ShaderStage,
+) -> Result<(), PushConstantError> {
+    let visible = ranges
+        .iter()
+        .filter(|range| range.stages.contains(stage_bit))
+        .collect::<Vec<_>>();
+    if visible.is_empty() {
+        return Err(PushConstantError::Invisible);
+    }
+    let mut ty_inner = &module.types[var.ty].inner;
+    //TODO: change naga's IR to avoid a pointer here
+    if let naga::TypeInner::Pointer { base, class: _ } = *ty_inner {
+        ty_inner = &module.types[base].inner;
+    }
+    let (start, end) = match *ty_inner {
+        naga::TypeInner::Struct { ref members } => {
+            let start = members.first().map_or(0, |member| member.offset);
+            let end = get_struct_size(module, members, BufferLayout::Storage)
+                .map_err(PushConstantError::Type)?;
+            (start as wgt::BufferAddress, end)
+        }
+        ref other => {
+            return Err(PushConstantError::Type(BindingError::UnsupportedType(
+                other.clone(),
+            )))
+        }
+    };
+    // The member offsets of the block are offsets into the push constant
+    // space, so the block has to lie within one of the visible ranges.
+    let covered = visible.iter().any(|range| {
+        range.range.start as wgt::BufferAddress <= start
+            && end <= range.range.end as wgt::BufferAddress
+    });
+    if !covered {
+        return Err(PushConstantError::OutOfRange {
+            start,
+            end,
+            range: visible[0].range.clone(),
+        });
+    }
+    Ok(())
+}
+
+fn find_entry_point<'a>(
+    module: &'a naga::Module,
     entry_point_name: &str,
     execution_model: spirv::ExecutionModel,
-    inputs: StageInterface<'a>,
-) -> Result<StageInterface<'a>, StageError> {
+) -> Result<&'a naga::EntryPoint, StageError> {
     // Since a shader module can have multiple entry points with the same name,
     // we need to look for one with the right execution model.
-    let entry_point = module
+    module
         .entry_points
         .iter()
         .find(|entry_point| {
             entry_point.name == entry_point_name && entry_point.exec_model == execution_model
         })
-        .ok_or(StageError::MissingEntryPoint(execution_model))?;
-    let stage_bit = match execution_model {
-        spirv::ExecutionModel::Vertex => wgt::ShaderStage::VERTEX,
-        spirv::ExecutionModel::Fragment => wgt::ShaderStage::FRAGMENT,
-        spirv::ExecutionModel::GLCompute => wgt::ShaderStage::COMPUTE,
-        // the entry point wouldn't match otherwise
-        _ => unreachable!(),
+        .ok_or_else(|| StageError::MissingEntryPoint {
+            name: entry_point_name.to_string(),
+            execution_model,
+            available: available_entry_points(module),
+        })
+}
+
+/// Lists the name and execution model of every entry point in the module.
+pub fn available_entry_points(module: &naga::Module) -> Vec<(String, spirv::ExecutionModel)> {
+    module
+        .entry_points
+        .iter()
+        .map(|entry_point| (entry_point.name.clone(), entry_point.exec_model))
+        .collect()
+}
+
+/// Builds the interface the vertex buffer layouts of a pipeline provide to
+/// the inputs of its vertex stage.
+///
+/// Vertex fetch expands every component to at least 32 bits: normalized
+/// formats are seen as floats, and raw integer formats as integers of the same
+/// signedness. Double formats keep their 64 bits. The shader has to declare its
+/// inputs accordingly.
+pub fn vertex_buffer_interface(
+    buffers: &[wgt::VertexBufferDescriptor],
+    features: wgt::Features,
+) -> Result<StageInterface<'static>, VertexFormatError> {
+    buffers
+        .iter()
+        .flat_map(|buffer| buffer.attributes.iter())
+        .map(|attribute| {
+            let ty = match map_vertex_format(attribute.format, features)? {
+                naga::TypeInner::Scalar { kind, width } => naga::TypeInner::Scalar {
+                    kind,
+                    width: width.max(32),
+                },
+                naga::TypeInner::Vector { size, kind, width } => naga::TypeInner::Vector {
+                    size,
+                    kind,
+                    width: width.max(32),
+                },
+                other => other,
+            };
+            Ok((attribute.shader_location, MaybeOwned::Owned(ty)))
+        })
+        .collect()
+}
+
+/// Returns the error for an input that the previous stage doesn't provide,
+/// listing what it does provide if anything.
+fn missing_input(inputs: &StageInterface) -> InputError {
+    if inputs.is_empty() {
+        return InputError::Missing;
+    }
+    let mut available = inputs
+        .iter()
+        .map(|(&location, _)| location)
+        .collect::<Vec<_>>();
+    available.sort_unstable();
+    InputError::UnmatchedLocation { available }
+}
+
+/// The pipeline layout and device capabilities a stage is validated against.
+#[derive(Clone, Debug, Default)]
+pub struct StageEnvironment<'a> {
+    /// Bind group layouts of the pipeline layout, indexed by set.
+    pub group_layouts: &'a [&'a BindEntryMap],
+    /// Push constant ranges of the pipeline layout.
+    pub push_constant_ranges: &'a [wgt::PushConstantRange],
+    /// Features enabled on the device.
+    pub features: wgt::Features,
+    /// Limits of the device.
+    pub limits: wgt::Limits,
+}
+
+/// A cheap key identifying the arguments of a `check_stage` call.
+///
+/// The key holds hashes of the entry point interface, the layouts, features,
+/// limits and inputs, so calls with equal keys have the same outcome unless
+/// the hashes collide. Callers that recreate pipelines often can store the
+/// key of a successful validation as a hint, but wgpu-core always validates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StageValidationKey {
+    interface: u64,
+    environment: u64,
+}
+
+impl StageValidationKey {
+    pub fn new(
+        module: &naga::Module,
+        env: &StageEnvironment,
+        entry_point_name: &str,
+        execution_model: spirv::ExecutionModel,
+        inputs: &StageInterface,
+    ) -> Result<Self, StageError> {
+        let interface = interface_fingerprint(module, entry_point_name, execution_model)?;
+
+        let mut hasher = fxhash::FxHasher::default();
+        env.group_layouts.len().hash(&mut hasher);
+        for layout in env.group_layouts {
+            let mut entries = layout.values().collect::<Vec<_>>();
+            entries.sort_by_key(|entry| entry.binding);
+            entries.hash(&mut hasher);
+        }
+        env.push_constant_ranges.hash(&mut hasher);
+        env.features.bits().hash(&mut hasher);
+        env.limits.hash(&mut hasher);
+        // Inputs without a module are built from vertex formats, so they
+        // don't refer to other types.
+        let mut input_hashes = inputs
+            .iter()
+            .map(|(&location, ty)| {
+                let mut hasher = fxhash::FxHasher::default();
+                hash_type_inner(inputs.module, &**ty, &mut hasher);
+                (location, hasher.finish())
+            })
+            .collect::<Vec<_>>();
+        input_hashes.sort_unstable();
+        input_hashes.hash(&mut hasher);
+
+        Ok(StageValidationKey {
+            interface,
+            environment: hasher.finish(),
+        })
+    }
+}
+
+pub fn check_stage<'a>(
+    module: &'a naga::Module,
+    env: &StageEnvironment,
+    entry_point_name: &str,
+    execution_model: spirv::ExecutionModel,
+    inputs: StageInterface<'a>,
+) -> Result<StageInterface<'a>, StageError> {
+    let entry_point = find_entry_point(module, entry_point_name, execution_model)?;
+    let stage_bit = map_execution_model(execution_model);
+    // Vertex inputs are fetched from buffers and expanded by the hardware,
+    // while varyings have to be passed through unchanged.
+    let width_rule = if stage_bit == wgt::ShaderStage::VERTEX {
+        WidthRule::Expand
+    } else {
+        WidthRule::Exact
     };
 
     let function = &module.functions[entry_point.function];
-    let mut outputs = StageInterface::default();
+    // A layout with too few bind groups is reported as a whole, rather than
+    // as a missing binding in the first set it lacks.
+    let required = used_sets(module, function)
+        .map(|set| set as usize + 1)
+        .max()
+        .unwrap_or(0);
+    if required > env.group_layouts.len() {
+        return Err(StageError::TooFewBindGroups {
+            required,
+            provided: env.group_layouts.len(),
+        });
+    }
+    let mut outputs = StageInterface {
+        module: Some(module),
+        ..StageInterface::default()
+    };
     for ((_, var), &usage) in module.global_variables.iter().zip(&function.global_usage) {
         if usage.is_empty() {
             continue;
         }
+        if var.class == spirv::StorageClass::PushConstant {
+            check_push_constant(module, var, env.push_constant_ranges, stage_bit)
+                .map_err(|error| StageError::PushConstant { error })?;
+            continue;
+        }
         match var.binding {
+            Some(naga::Binding::BuiltIn(builtin)) => {
+                if !builtin_stages(builtin).contains(stage_bit) {
+                    return Err(StageError::InvalidBuiltinForStage {
+                        builtin,
+                        stage: stage_bit,
+                    });
+                }
+            }
             Some(naga::Binding::Descriptor { set, binding }) => {
-                let result = group_layouts
+                let result = env
+                    .group_layouts
                     .get(set as usize)
                     .and_then(|map| map.get(&binding))
                     .ok_or(BindingError::Missing)
@@ -697,11 +2053,22 @@ pub fn check_stage<'a>(
                             Err(BindingError::Invisible)
                         }
                     })
-                    .and_then(|entry| check_binding(module, var, entry, usage));
+                    .and_then(|entry| {
+                        check_binding(
+                            module,
+                            var,
+                            entry,
+                            usage,
+                            stage_bit,
+                            env.features,
+                            &env.limits,
+                        )
+                    });
                 if let Err(error) = result {
                     return Err(StageError::Binding {
                         set,
                         binding,
+                        stage: stage_bit,
                         error,
                     });
                 }
@@ -713,26 +2080,2918 @@ pub fn check_stage<'a>(
                     ty = &module.types[base].inner;
                 }
                 if usage.contains(naga::GlobalUse::STORE) {
-                    outputs.insert(location, MaybeOwned::Borrowed(ty));
+                    if outputs.insert(location, MaybeOwned::Borrowed(ty)).is_some() {
+                        return Err(StageError::DuplicateOutputLocation { location });
+                    }
                 } else {
-                    let result =
-                        inputs
-                            .get(&location)
-                            .ok_or(InputError::Missing)
-                            .and_then(|provided| {
-                                if is_sub_type(ty, provided) {
-                                    Ok(())
-                                } else {
-                                    Err(InputError::WrongType)
-                                }
-                            });
+                    let result = inputs
+                        .get(&location)
+                        .ok_or_else(|| missing_input(&inputs))
+                        .and_then(|provided| {
+                            let modules = inputs
+                                .module()
+                                .map(|provided_module| (module, provided_module));
+                            if is_sub_type(ty, provided, modules, width_rule) {
+                                Ok(())
+                            } else {
+                                Err(InputError::WrongType)
+                            }
+                        });
                     if let Err(error) = result {
-                        return Err(StageError::Input { location, error });
+                        return Err(StageError::Input {
+                            location,
+                            stage: stage_bit,
+                            error,
+                        });
                     }
                 }
             }
             _ => {}
         }
     }
+    if execution_model == spirv::ExecutionModel::GLCompute {
+        let used = workgroup_storage_size(module, function)?;
+        let limit = env.limits.max_compute_workgroup_storage_size as wgt::BufferAddress;
+        if used > limit {
+            return Err(StageError::WorkgroupStorageExceeded { used, limit });
+        }
+    }
+    if stage_bit == wgt::ShaderStage::VERTEX {
+        let count = outputs.len() as u32;
+        let limit = env.limits.max_inter_stage_shader_variables;
+        if count > limit {
+            return Err(StageError::TooManyInterStageVariables { count, limit });
+        }
+        // Each location takes up as many components as the vector in it,
+        // and a matrix takes up one location per column.
+        let used = outputs
+            .iter()
+            .map(|(_, ty)| varying_components(module, ty))
+            .sum::<u32>();
+        let limit = env.limits.max_inter_stage_shader_components;
+        if used > limit {
+            return Err(StageError::TooManyInterStageComponents { used, limit });
+        }
+    }
     Ok(outputs)
 }
+
+/// Returns the descriptor set of every binding the function uses.
+fn used_sets<'a>(
+    module: &'a naga::Module,
+    function: &'a naga::Function,
+) -> impl Iterator<Item = u32> + 'a {
+    module
+        .global_variables
+        .iter()
+        .zip(&function.global_usage)
+        .filter_map(|((_, var), usage)| match var.binding {
+            Some(naga::Binding::Descriptor { set, .. })
+                if !usage.is_empty() && var.class != spirv::StorageClass::PushConstant =>
+            {
+                Some(set)
+            }
+            _ => None,
+        })
+}
+
+/// Checks that a pipeline layout with `bind_group_count` bind groups and the
+/// given push constant ranges can serve the selected entry points of a module.
+///
+/// This is a cheap precondition for callers that want to know whether a layout
+/// fits a module before validating its stages. `check_stage` makes the same
+/// checks, with more detail, so the device doesn't run this one as well.
+/// Entry points that are missing from the module are skipped.
+pub fn validate_pipeline_layout_for_module(
+    bind_group_count: usize,
+    push_constant_ranges: &[wgt::PushConstantRange],
+    module: &naga::Module,
+    entry_points: &[(&str, spirv::ExecutionModel)],
+) -> Result<(), PipelineLayoutError> {
+    for &(name, execution_model) in entry_points {
+        let entry_point = match module
+            .entry_points
+            .iter()
+            .find(|ep| ep.name == name && ep.exec_model == execution_model)
+        {
+            Some(entry_point) => entry_point,
+            None => continue,
+        };
+        let stage = map_execution_model(execution_model);
+        let function = &module.functions[entry_point.function];
+        if let Some(set) = used_sets(module, function).find(|&set| set as usize >= bind_group_count)
+        {
+            return Err(PipelineLayoutError::MissingBindGroup {
+                set,
+                count: bind_group_count,
+            });
+        }
+        let uses_push_constants = module
+            .global_variables
+            .iter()
+            .zip(&function.global_usage)
+            .any(|((_, var), usage)| {
+                !usage.is_empty() && var.class == spirv::StorageClass::PushConstant
+            });
+        if uses_push_constants
+            && !push_constant_ranges
+                .iter()
+                .any(|range| range.stages.contains(stage))
+        {
+            return Err(PipelineLayoutError::MissingPushConstants { stage });
+        }
+    }
+    Ok(())
+}
+
+/// Fills in the `min_binding_size` of every buffer entry in `entries` that
+/// doesn't specify one, using the size of the matching global in `group` of
+/// the shader module.
+///
+/// Entries whose shader type can't be sized are left untouched, so that
+/// `check_stage` reports the problem later.
+pub fn infer_min_binding_sizes(entries: &mut BindEntryMap, module: &naga::Module, group: u32) {
+    for (_, var) in module.global_variables.iter() {
+        let binding = match var.binding {
+            Some(naga::Binding::Descriptor { set, binding }) if set == group => binding,
+            _ => continue,
+        };
+        let (min_binding_size, layout) = match entries.get_mut(&binding).map(|entry| &mut entry.ty)
+        {
+            Some(BindingType::UniformBuffer {
+                min_binding_size, ..
+            }) if min_binding_size.is_none() => (min_binding_size, BufferLayout::Uniform),
+            Some(BindingType::StorageBuffer {
+                min_binding_size, ..
+            }) if min_binding_size.is_none() => (min_binding_size, BufferLayout::Storage),
+            _ => continue,
+        };
+        let mut ty_inner = &module.types[var.ty].inner;
+        //TODO: change naga's IR to avoid a pointer here
+        if let naga::TypeInner::Pointer { base, class: _ } = *ty_inner {
+            ty_inner = &module.types[base].inner;
+        }
+        if let naga::TypeInner::Struct { ref members } = *ty_inner {
+            if let Ok(size) = get_struct_size(module, members, layout) {
+                *min_binding_size = wgt::BufferSize::new(size);
+            }
+        }
+    }
+}
+
+/// Infers the binding type a layout needs to declare for a descriptor global,
+/// given its combined usage across the entry points.
+///
+/// Returns `None` for types that don't determine a binding type, such as
+/// storage images, whose format isn't part of the module.
+fn derive_binding_type(
+    module: &naga::Module,
+    var: &naga::GlobalVariable,
+    usage: naga::GlobalUse,
+) -> Option<BindingType> {
+    let mut ty_inner = &module.types[var.ty].inner;
+    //TODO: change naga's IR to avoid a pointer here
+    if let naga::TypeInner::Pointer { base, class: _ } = *ty_inner {
+        ty_inner = &module.types[base].inner;
+    }
+    Some(match *ty_inner {
+        naga::TypeInner::Struct { ref members } => match var.class {
+            spirv::StorageClass::Uniform => BindingType::UniformBuffer {
+                dynamic: false,
+                min_binding_size: get_struct_size(module, members, BufferLayout::Uniform)
+                    .ok()
+                    .and_then(wgt::BufferSize::new),
+            },
+            spirv::StorageClass::StorageBuffer => BindingType::StorageBuffer {
+                dynamic: false,
+                min_binding_size: get_struct_size(module, members, BufferLayout::Storage)
+                    .ok()
+                    .and_then(wgt::BufferSize::new),
+                readonly: !usage.contains(naga::GlobalUse::STORE),
+            },
+            _ => return None,
+        },
+        naga::TypeInner::Sampler { comparison } => BindingType::Sampler { comparison },
+        naga::TypeInner::Image { base, dim, flags }
+            if flags.contains(naga::ImageFlags::SAMPLED) =>
+        {
+            let dimension = match (dim, flags.contains(naga::ImageFlags::ARRAYED)) {
+                (spirv::Dim::Dim1D, false) => wgt::TextureViewDimension::D1,
+                (spirv::Dim::Dim2D, false) => wgt::TextureViewDimension::D2,
+                (spirv::Dim::Dim2D, true) => wgt::TextureViewDimension::D2Array,
+                (spirv::Dim::Dim3D, false) => wgt::TextureViewDimension::D3,
+                (spirv::Dim::DimCube, false) => wgt::TextureViewDimension::Cube,
+                (spirv::Dim::DimCube, true) => wgt::TextureViewDimension::CubeArray,
+                _ => return None,
+            };
+            let kind = match module.types[base].inner {
+                naga::TypeInner::Scalar { kind, .. } | naga::TypeInner::Vector { kind, .. } => kind,
+                _ => return None,
+            };
+            let component_type = match kind {
+                naga::ScalarKind::Float => wgt::TextureComponentType::Float,
+                naga::ScalarKind::Sint => wgt::TextureComponentType::Sint,
+                naga::ScalarKind::Uint => wgt::TextureComponentType::Uint,
+                naga::ScalarKind::Bool => return None,
+            };
+            BindingType::SampledTexture {
+                dimension,
+                component_type,
+                multisampled: flags.contains(naga::ImageFlags::MULTISAMPLED),
+            }
+        }
+        _ => return None,
+    })
+}
+
+/// Builds bind group layout entries, keyed by set, for every descriptor
+/// global of the module, visible to `stage`.
+///
+/// Globals whose binding type can't be inferred from the module are left
+/// out, so the caller has to describe those itself.
+pub fn derive_bind_group_layout_entries(
+    module: &naga::Module,
+    stage: wgt::ShaderStage,
+) -> FastHashMap<u32, BindEntryMap> {
+    let mut sets = FastHashMap::<u32, BindEntryMap>::default();
+    for (index, (_, var)) in module.global_variables.iter().enumerate() {
+        let (set, binding) = match var.binding {
+            Some(naga::Binding::Descriptor { set, binding }) => (set, binding),
+            _ => continue,
+        };
+        let usage =
+            module
+                .entry_points
+                .iter()
+                .fold(naga::GlobalUse::empty(), |usage, entry_point| {
+                    let function = &module.functions[entry_point.function];
+                    function
+                        .global_usage
+                        .get(index)
+                        .map_or(usage, |&global_use| usage | global_use)
+                });
+        if let Some(ty) = derive_binding_type(module, var, usage) {
+            sets.entry(set)
+                .or_default()
+                .insert(binding, BindGroupLayoutEntry::new(binding, stage, ty));
+        }
+    }
+    sets
+}
+
+/// Returns the highest descriptor set index used by any entry point of the
+/// module, or `None` if no entry point uses a descriptor binding.
+pub fn max_set_used(module: &naga::Module) -> Option<u32> {
+    module
+        .entry_points
+        .iter()
+        .flat_map(|entry_point| used_sets(module, &module.functions[entry_point.function]))
+        .max()
+}
+
+/// Returns the `(set, binding)` pairs that are declared in `group_layouts`,
+/// but not referenced by any entry point of the module, sorted by set and
+/// then by binding.
+///
+/// Unused entries are valid, this is only meant to help finding layout
+/// entries that are wasted.
+///
+/// ```
+/// use wgpu_core::{binding_model::BindEntryMap, unused_bindings};
+///
+/// let mut entries = BindEntryMap::default();
+/// entries.insert(
+///     0,
+///     wgt::BindGroupLayoutEntry::new(
+///         0,
+///         wgt::ShaderStage::FRAGMENT,
+///         wgt::BindingType::Sampler { comparison: false },
+///     ),
+/// );
+/// // A module without entry points doesn't use any binding.
+/// let module = naga::Module::generate_empty();
+/// assert_eq!(unused_bindings(&module, &[&entries]), vec![(0, 0)]);
+/// ```
+pub fn unused_bindings(module: &naga::Module, group_layouts: &[&BindEntryMap]) -> Vec<(u32, u32)> {
+    let used = module
+        .entry_points
+        .iter()
+        .flat_map(|entry_point| {
+            let function = &module.functions[entry_point.function];
+            module
+                .global_variables
+                .iter()
+                .zip(&function.global_usage)
+                .filter_map(|((_, var), usage)| match var.binding {
+                    Some(naga::Binding::Descriptor { set, binding }) if !usage.is_empty() => {
+                        Some((set, binding))
+                    }
+                    _ => None,
+                })
+        })
+        .collect::<Vec<_>>();
+    let mut unused = group_layouts
+        .iter()
+        .enumerate()
+        .flat_map(|(set, map)| map.keys().map(move |&binding| (set as u32, binding)))
+        .filter(|pair| !used.contains(pair))
+        .collect::<Vec<_>>();
+    unused.sort_unstable();
+    unused
+}
+
+/// Returns the size of the workgroup variables a function uses.
+fn workgroup_storage_size(
+    module: &naga::Module,
+    function: &naga::Function,
+) -> Result<wgt::BufferAddress, StageError> {
+    let mut used = 0;
+    for ((_, var), usage) in module.global_variables.iter().zip(&function.global_usage) {
+        if usage.is_empty() || var.class != spirv::StorageClass::Workgroup {
+            continue;
+        }
+        let ty = match module.types[var.ty].inner {
+            naga::TypeInner::Pointer { base, class: _ } => base,
+            _ => var.ty,
+        };
+        used += get_aligned_type_size(module, ty, BufferLayout::Storage, false)
+            .map_err(|error| StageError::InvalidWorkgroupStorage { error })?;
+    }
+    Ok(used)
+}
+
+/// Returns the locations of output varyings that an entry point declares but
+/// never writes to.
+///
+/// Naga only records whether a function stores to a global at all, so outputs
+/// that are written on some control flow paths only aren't reported.
+pub fn unwritten_outputs(
+    module: &naga::Module,
+    entry_point_name: &str,
+    execution_model: spirv::ExecutionModel,
+) -> Result<Vec<wgt::ShaderLocation>, StageError> {
+    let entry_point = find_entry_point(module, entry_point_name, execution_model)?;
+    let function = &module.functions[entry_point.function];
+    Ok(module
+        .global_variables
+        .iter()
+        .zip(&function.global_usage)
+        .filter_map(|((_, var), usage)| match var.binding {
+            Some(naga::Binding::Location(location))
+                if var.class == spirv::StorageClass::Output
+                    && !usage.contains(naga::GlobalUse::STORE) =>
+            {
+                Some(location)
+            }
+            _ => None,
+        })
+        .collect())
+}
+
+/// Returns the locations of the input varyings that an entry point reads.
+pub fn input_locations(
+    module: &naga::Module,
+    entry_point_name: &str,
+    execution_model: spirv::ExecutionModel,
+) -> Result<Vec<wgt::ShaderLocation>, StageError> {
+    let entry_point = find_entry_point(module, entry_point_name, execution_model)?;
+    let function = &module.functions[entry_point.function];
+    Ok(module
+        .global_variables
+        .iter()
+        .zip(&function.global_usage)
+        .filter_map(|((_, var), usage)| match var.binding {
+            Some(naga::Binding::Location(location))
+                if var.class == spirv::StorageClass::Input
+                    && usage.contains(naga::GlobalUse::LOAD) =>
+            {
+                Some(location)
+            }
+            _ => None,
+        })
+        .collect())
+}
+
+#[test]
+fn test_missing_entry_point_name() {
+    let module = naga::Module::generate_empty();
+    let error = check_stage(
+        &module,
+        &StageEnvironment::default(),
+        "mian",
+        spirv::ExecutionModel::Fragment,
+        StageInterface::default(),
+    )
+    .err()
+    .unwrap();
+    assert!(error.to_string().contains("\"mian\""));
+}
+
+#[test]
+fn test_missing_fragment_output() {
+    let mut outputs = StageInterface::default();
+    outputs.insert(
+        0,
+        MaybeOwned::Owned(naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        }),
+    );
+    let formats = [
+        wgt::TextureFormat::Rgba8Unorm,
+        wgt::TextureFormat::Rgba8Unorm,
+    ];
+    match check_fragment_outputs(&outputs, &formats) {
+        Err(FragmentOutputError::Missing(locations)) => assert_eq!(locations, vec![1]),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_incompatible_fragment_output() {
+    let mut outputs = StageInterface::default();
+    outputs.insert(
+        0,
+        MaybeOwned::Owned(naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        }),
+    );
+    match check_fragment_outputs(&outputs, &[wgt::TextureFormat::Rgba8Uint]) {
+        Err(FragmentOutputError::IncompatibleFormat { location: 0, .. }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_unsupported_struct_member() {
+    let mut module = naga::Module::generate_empty();
+    let sampler = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Sampler { comparison: false },
+    });
+    let structure = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Struct {
+            members: vec![naga::StructMember {
+                name: None,
+                binding: None,
+                ty: sampler,
+                offset: 0,
+            }],
+        },
+    });
+    match get_aligned_type_size(&module, structure, BufferLayout::Storage, false) {
+        Err(BindingError::UnsupportedType(naga::TypeInner::Sampler { .. })) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[cfg(test)]
+fn add_global(
+    module: &mut naga::Module,
+    class: spirv::StorageClass,
+    binding: naga::Binding,
+    inner: naga::TypeInner,
+) -> naga::Handle<naga::GlobalVariable> {
+    let ty = module.types.append(naga::Type { name: None, inner });
+    module.global_variables.append(naga::GlobalVariable {
+        name: None,
+        class,
+        binding: Some(binding),
+        ty,
+    })
+}
+
+#[cfg(test)]
+fn add_entry_point(
+    module: &mut naga::Module,
+    exec_model: spirv::ExecutionModel,
+    global_usage: Vec<naga::GlobalUse>,
+) {
+    let function = module.functions.append(naga::Function {
+        name: None,
+        control: spirv::FunctionControl::empty(),
+        parameter_types: Vec::new(),
+        return_type: None,
+        global_usage,
+        local_variables: naga::Arena::new(),
+        expressions: naga::Arena::new(),
+        body: Vec::new(),
+    });
+    module.entry_points.push(naga::EntryPoint {
+        exec_model,
+        name: "main".to_string(),
+        function,
+    });
+}
+
+/// Adds a global bound at `(0, 0)` with the given type to `module`, and
+/// checks it against a layout entry of type `ty` from the compute stage.
+#[cfg(test)]
+fn check_single_binding(
+    module: &mut naga::Module,
+    inner: naga::TypeInner,
+    ty: BindingType,
+    usage: naga::GlobalUse,
+) -> Result<(), BindingError> {
+    let class = match ty {
+        BindingType::UniformBuffer { .. } => spirv::StorageClass::Uniform,
+        BindingType::StorageBuffer { .. } => spirv::StorageClass::StorageBuffer,
+        _ => spirv::StorageClass::UniformConstant,
+    };
+    let var = add_global(
+        module,
+        class,
+        naga::Binding::Descriptor { set: 0, binding: 0 },
+        inner,
+    );
+    let entry = BindGroupLayoutEntry::new(0, wgt::ShaderStage::COMPUTE, ty);
+    check_binding(
+        module,
+        &module.global_variables[var],
+        &entry,
+        usage,
+        wgt::ShaderStage::COMPUTE,
+        wgt::Features::empty(),
+        &wgt::Limits::default(),
+    )
+}
+
+#[test]
+fn test_builtin_stage() {
+    let mut module = naga::Module::generate_empty();
+    add_global(
+        &mut module,
+        spirv::StorageClass::Input,
+        naga::Binding::BuiltIn(naga::BuiltIn::FragCoord),
+        naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        },
+    );
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Vertex,
+        vec![naga::GlobalUse::LOAD],
+    );
+    match check_stage(
+        &module,
+        &StageEnvironment::default(),
+        "main",
+        spirv::ExecutionModel::Vertex,
+        StageInterface::default(),
+    ) {
+        Err(StageError::InvalidBuiltinForStage {
+            builtin: naga::BuiltIn::FragCoord,
+            stage: wgt::ShaderStage::VERTEX,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other.err()),
+    }
+}
+
+#[test]
+fn test_infer_min_binding_size() {
+    let uniform_struct = |module: &mut naga::Module, size| {
+        let vec4 = module.types.append(naga::Type {
+            name: None,
+            inner: naga::TypeInner::Vector {
+                size: naga::VectorSize::Quad,
+                kind: naga::ScalarKind::Float,
+                width: 32,
+            },
+        });
+        let members = (0..size / 16)
+            .map(|i| naga::StructMember {
+                name: None,
+                binding: None,
+                ty: vec4,
+                offset: i * 16,
+            })
+            .collect();
+        add_global(
+            module,
+            spirv::StorageClass::Uniform,
+            naga::Binding::Descriptor { set: 0, binding: 0 },
+            naga::TypeInner::Struct { members },
+        )
+    };
+
+    let mut small = naga::Module::generate_empty();
+    uniform_struct(&mut small, 16);
+    let mut entries = BindEntryMap::default();
+    entries.insert(
+        0,
+        BindGroupLayoutEntry::new(
+            0,
+            wgt::ShaderStage::VERTEX,
+            BindingType::UniformBuffer {
+                dynamic: false,
+                min_binding_size: None,
+            },
+        ),
+    );
+    infer_min_binding_sizes(&mut entries, &small, 0);
+    match entries[&0].ty {
+        BindingType::UniformBuffer {
+            min_binding_size: Some(size),
+            ..
+        } => assert_eq!(size.get(), 16),
+        ref other => panic!("size was not inferred: {:?}", other),
+    }
+
+    let mut large = naga::Module::generate_empty();
+    let var = uniform_struct(&mut large, 32);
+    match check_binding(
+        &large,
+        &large.global_variables[var],
+        &entries[&0],
+        naga::GlobalUse::LOAD,
+        wgt::ShaderStage::VERTEX,
+        wgt::Features::empty(),
+        &wgt::Limits::default(),
+    ) {
+        Err(BindingError::WrongBufferSize(32)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_pipeline_layout_for_module() {
+    let mut module = naga::Module::generate_empty();
+    add_global(
+        &mut module,
+        spirv::StorageClass::Uniform,
+        naga::Binding::Descriptor { set: 1, binding: 0 },
+        naga::TypeInner::Struct {
+            members: Vec::new(),
+        },
+    );
+    let push_constants = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Struct {
+            members: Vec::new(),
+        },
+    });
+    module.global_variables.append(naga::GlobalVariable {
+        name: None,
+        class: spirv::StorageClass::PushConstant,
+        binding: None,
+        ty: push_constants,
+    });
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Vertex,
+        vec![naga::GlobalUse::LOAD, naga::GlobalUse::LOAD],
+    );
+    let entry_points = [("main", spirv::ExecutionModel::Vertex)];
+    let ranges = [wgt::PushConstantRange {
+        stages: wgt::ShaderStage::VERTEX,
+        range: 0..16,
+    }];
+
+    match validate_pipeline_layout_for_module(1, &ranges, &module, &entry_points) {
+        Err(PipelineLayoutError::MissingBindGroup { set: 1, count: 1 }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match validate_pipeline_layout_for_module(2, &[], &module, &entry_points) {
+        Err(PipelineLayoutError::MissingPushConstants {
+            stage: wgt::ShaderStage::VERTEX,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(validate_pipeline_layout_for_module(2, &ranges, &module, &entry_points).is_ok());
+}
+
+#[test]
+fn test_push_constant_block() {
+    let mut module = naga::Module::generate_empty();
+    let vec4 = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        },
+    });
+    let block = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Struct {
+            members: vec![naga::StructMember {
+                name: None,
+                binding: None,
+                ty: vec4,
+                offset: 0,
+            }],
+        },
+    });
+    module.global_variables.append(naga::GlobalVariable {
+        name: None,
+        class: spirv::StorageClass::PushConstant,
+        binding: None,
+        ty: block,
+    });
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Fragment,
+        vec![naga::GlobalUse::LOAD],
+    );
+    let check = |ranges: &[wgt::PushConstantRange]| {
+        check_stage(
+            &module,
+            &StageEnvironment {
+                push_constant_ranges: ranges,
+                ..StageEnvironment::default()
+            },
+            "main",
+            spirv::ExecutionModel::Fragment,
+            StageInterface::default(),
+        )
+        .err()
+    };
+
+    let too_small = [wgt::PushConstantRange {
+        stages: wgt::ShaderStage::FRAGMENT,
+        range: 0..8,
+    }];
+    match check(&too_small) {
+        Some(StageError::PushConstant {
+            error:
+                PushConstantError::OutOfRange {
+                    start: 0, end: 16, ..
+                },
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    let vertex_only = [wgt::PushConstantRange {
+        stages: wgt::ShaderStage::VERTEX,
+        range: 0..16,
+    }];
+    match check(&vertex_only) {
+        Some(StageError::PushConstant {
+            error: PushConstantError::Invisible,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    // The block starts at offset 0, so a range of the same size further
+    // into the push constant space doesn't cover it.
+    let shifted = [wgt::PushConstantRange {
+        stages: wgt::ShaderStage::FRAGMENT,
+        range: 16..32,
+    }];
+    match check(&shifted) {
+        Some(StageError::PushConstant {
+            error:
+                PushConstantError::OutOfRange {
+                    start: 0, end: 16, ..
+                },
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    let matching = [
+        wgt::PushConstantRange {
+            stages: wgt::ShaderStage::VERTEX,
+            range: 0..16,
+        },
+        wgt::PushConstantRange {
+            stages: wgt::ShaderStage::FRAGMENT,
+            range: 0..16,
+        },
+    ];
+    assert!(check(&matching).is_none());
+}
+
+#[test]
+fn test_vertex_buffer_layout() {
+    let check = |stride, attributes: &[wgt::VertexAttributeDescriptor]| {
+        check_vertex_buffer_layout(&wgt::VertexBufferDescriptor {
+            stride,
+            step_mode: wgt::InputStepMode::Vertex,
+            attributes,
+        })
+    };
+    let attribute = |shader_location, offset, format| wgt::VertexAttributeDescriptor {
+        offset,
+        format,
+        shader_location,
+    };
+
+    assert!(check(
+        20,
+        &[
+            attribute(0, 0, wgt::VertexFormat::Float3),
+            attribute(1, 12, wgt::VertexFormat::Float2),
+        ],
+    )
+    .is_ok());
+    match check(16, &[attribute(0, 8, wgt::VertexFormat::Float3)]) {
+        Err(VertexBufferError::AttributeOverrun {
+            location: 0,
+            end: 20,
+            stride: 16,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match check(
+        32,
+        &[
+            attribute(0, 0, wgt::VertexFormat::Float4),
+            attribute(1, 8, wgt::VertexFormat::Float2),
+        ],
+    ) {
+        Err(VertexBufferError::OverlappingAttributes {
+            first: 0,
+            second: 1,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match check(32, &[attribute(2, 6, wgt::VertexFormat::Float)]) {
+        Err(VertexBufferError::MisalignedOffset {
+            location: 2,
+            offset: 6,
+            alignment: 4,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(check(8, &[attribute(3, 6, wgt::VertexFormat::Uchar2)]).is_ok());
+}
+
+#[test]
+fn test_max_set_used() {
+    let mut module = naga::Module::generate_empty();
+    assert_eq!(max_set_used(&module), None);
+    for &set in &[0, 2, 5] {
+        add_global(
+            &mut module,
+            spirv::StorageClass::UniformConstant,
+            naga::Binding::Descriptor { set, binding: 0 },
+            naga::TypeInner::Sampler { comparison: false },
+        );
+    }
+    // Set 5 is declared, but not used by the entry point.
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Fragment,
+        vec![
+            naga::GlobalUse::LOAD,
+            naga::GlobalUse::LOAD,
+            naga::GlobalUse::empty(),
+        ],
+    );
+    assert_eq!(max_set_used(&module), Some(2));
+}
+
+#[test]
+fn test_vertex_buffer_interface() {
+    let mut module = naga::Module::generate_empty();
+    add_global(
+        &mut module,
+        spirv::StorageClass::Input,
+        naga::Binding::Location(1),
+        naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        },
+    );
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Vertex,
+        vec![naga::GlobalUse::LOAD],
+    );
+    let check = |format, shader_location| {
+        let attributes = [wgt::VertexAttributeDescriptor {
+            offset: 0,
+            format,
+            shader_location,
+        }];
+        let buffers = [wgt::VertexBufferDescriptor {
+            stride: 16,
+            step_mode: wgt::InputStepMode::Vertex,
+            attributes: &attributes,
+        }];
+        check_stage(
+            &module,
+            &StageEnvironment::default(),
+            "main",
+            spirv::ExecutionModel::Vertex,
+            vertex_buffer_interface(&buffers, wgt::Features::empty()).unwrap(),
+        )
+        .err()
+    };
+
+    assert!(check(wgt::VertexFormat::Float4, 1).is_none());
+    match check(wgt::VertexFormat::Float4, 0) {
+        Some(StageError::Input {
+            location: 1,
+            stage: wgt::ShaderStage::VERTEX,
+            error: InputError::UnmatchedLocation { ref available },
+        }) => assert_eq!(*available, vec![0]),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match check(wgt::VertexFormat::Int4, 1) {
+        Some(StageError::Input {
+            location: 1,
+            stage: wgt::ShaderStage::VERTEX,
+            error: InputError::WrongType,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_zero_stride_runtime_array() {
+    let mut module = naga::Module::generate_empty();
+    let empty = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Struct {
+            members: Vec::new(),
+        },
+    });
+    let array = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Array {
+            base: empty,
+            size: naga::ArraySize::Dynamic,
+            stride: None,
+        },
+    });
+    let result = check_single_binding(
+        &mut module,
+        naga::TypeInner::Struct {
+            members: vec![naga::StructMember {
+                name: None,
+                binding: None,
+                ty: array,
+                offset: 0,
+            }],
+        },
+        BindingType::StorageBuffer {
+            dynamic: false,
+            min_binding_size: None,
+            readonly: true,
+        },
+        naga::GlobalUse::LOAD,
+    );
+    assert!(matches!(result, Err(BindingError::ZeroStrideRuntimeArray)));
+}
+
+#[test]
+fn test_binding_error_stage() {
+    let mut module = naga::Module::generate_empty();
+    add_global(
+        &mut module,
+        spirv::StorageClass::UniformConstant,
+        naga::Binding::Descriptor { set: 0, binding: 1 },
+        naga::TypeInner::Sampler { comparison: false },
+    );
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Fragment,
+        vec![naga::GlobalUse::LOAD],
+    );
+    let mut entries = BindEntryMap::default();
+    entries.insert(
+        1,
+        BindGroupLayoutEntry::new(
+            1,
+            wgt::ShaderStage::VERTEX,
+            BindingType::Sampler { comparison: false },
+        ),
+    );
+    let error = check_stage(
+        &module,
+        &StageEnvironment {
+            group_layouts: &[&entries],
+            ..StageEnvironment::default()
+        },
+        "main",
+        spirv::ExecutionModel::Fragment,
+        StageInterface::default(),
+    )
+    .err()
+    .unwrap();
+    match error {
+        StageError::Binding {
+            set: 0,
+            binding: 1,
+            stage: wgt::ShaderStage::FRAGMENT,
+            error: BindingError::Invisible,
+        } => {}
+        ref other => panic!("unexpected error: {:?}", other),
+    }
+    assert!(error.to_string().contains("FRAGMENT"));
+}
+
+#[test]
+fn test_packed_format_outputs() {
+    let float_vector = |size| naga::TypeInner::Vector {
+        size,
+        kind: naga::ScalarKind::Float,
+        width: 32,
+    };
+    assert!(check_texture_format(
+        wgt::TextureFormat::Rgb10a2Unorm,
+        &float_vector(naga::VectorSize::Quad),
+    ));
+    assert!(check_texture_format(
+        wgt::TextureFormat::Rg11b10Float,
+        &float_vector(naga::VectorSize::Tri),
+    ));
+    assert!(!check_texture_format(
+        wgt::TextureFormat::Rg11b10Float,
+        &float_vector(naga::VectorSize::Bi),
+    ));
+    assert!(!check_texture_format(
+        wgt::TextureFormat::Rgb10a2Unorm,
+        &naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Uint,
+            width: 32,
+        },
+    ));
+}
+
+#[test]
+fn test_read_write_storage_texture() {
+    let mut module = naga::Module::generate_empty();
+    let texel = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        },
+    });
+    let var = add_global(
+        &mut module,
+        spirv::StorageClass::UniformConstant,
+        naga::Binding::Descriptor { set: 0, binding: 0 },
+        naga::TypeInner::Image {
+            base: texel,
+            dim: spirv::Dim::Dim2D,
+            flags: naga::ImageFlags::empty(),
+        },
+    );
+    let entry = BindGroupLayoutEntry::new(
+        0,
+        wgt::ShaderStage::COMPUTE,
+        BindingType::StorageTexture {
+            dimension: wgt::TextureViewDimension::D2,
+            format: wgt::TextureFormat::Rgba32Float,
+            readonly: false,
+        },
+    );
+    let check = |features| {
+        check_binding(
+            &module,
+            &module.global_variables[var],
+            &entry,
+            naga::GlobalUse::LOAD | naga::GlobalUse::STORE,
+            wgt::ShaderStage::COMPUTE,
+            features,
+            &wgt::Limits::default(),
+        )
+    };
+    match check(wgt::Features::empty()) {
+        Err(BindingError::MissingFeature(wgt::Features::STORAGE_TEXTURE_READ_WRITE)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(check(wgt::Features::STORAGE_TEXTURE_READ_WRITE).is_ok());
+}
+
+#[test]
+fn test_stage_interface_inline_capacity() {
+    let scalar = |kind| MaybeOwned::Owned(naga::TypeInner::Scalar { kind, width: 32 });
+    let mut interface = StageInterface::default();
+    for location in 0..STAGE_INTERFACE_INLINE_LOCATIONS as wgt::ShaderLocation {
+        assert!(interface
+            .insert(location, scalar(naga::ScalarKind::Float))
+            .is_none());
+    }
+    // Up to the inline capacity, the entries stay in the small vector.
+    assert!(!interface.entries.spilled());
+
+    // Inserting at an occupied location replaces the entry, like a map does.
+    match interface
+        .insert(2, scalar(naga::ScalarKind::Uint))
+        .as_deref()
+    {
+        Some(&naga::TypeInner::Scalar {
+            kind: naga::ScalarKind::Float,
+            ..
+        }) => {}
+        _ => panic!("the previous entry was not returned"),
+    }
+    assert_eq!(interface.len(), STAGE_INTERFACE_INLINE_LOCATIONS);
+    match *interface[&2] {
+        naga::TypeInner::Scalar {
+            kind: naga::ScalarKind::Uint,
+            ..
+        } => {}
+        _ => panic!("the entry was not replaced"),
+    }
+    assert!(interface
+        .get(&(STAGE_INTERFACE_INLINE_LOCATIONS as wgt::ShaderLocation))
+        .is_none());
+
+    // One more location moves them to the heap.
+    interface.insert(
+        STAGE_INTERFACE_INLINE_LOCATIONS as wgt::ShaderLocation,
+        scalar(naga::ScalarKind::Float),
+    );
+    assert!(interface.entries.spilled());
+}
+
+#[test]
+fn test_unwritten_vertex_output() {
+    let float = naga::TypeInner::Scalar {
+        kind: naga::ScalarKind::Float,
+        width: 32,
+    };
+    let mut vertex = naga::Module::generate_empty();
+    for location in 0..2 {
+        add_global(
+            &mut vertex,
+            spirv::StorageClass::Output,
+            naga::Binding::Location(location),
+            float.clone(),
+        );
+    }
+    // Location 1 is declared, but never assigned.
+    add_entry_point(
+        &mut vertex,
+        spirv::ExecutionModel::Vertex,
+        vec![naga::GlobalUse::STORE, naga::GlobalUse::empty()],
+    );
+    let unwritten = unwritten_outputs(&vertex, "main", spirv::ExecutionModel::Vertex).unwrap();
+    assert_eq!(unwritten, vec![1]);
+
+    let mut fragment = naga::Module::generate_empty();
+    for location in 0..2 {
+        add_global(
+            &mut fragment,
+            spirv::StorageClass::Input,
+            naga::Binding::Location(location),
+            float.clone(),
+        );
+    }
+    add_entry_point(
+        &mut fragment,
+        spirv::ExecutionModel::Fragment,
+        vec![naga::GlobalUse::LOAD, naga::GlobalUse::LOAD],
+    );
+    let inputs = input_locations(&fragment, "main", spirv::ExecutionModel::Fragment).unwrap();
+    assert_eq!(inputs, vec![0, 1]);
+    let interface = check_stage(
+        &vertex,
+        &StageEnvironment::default(),
+        "main",
+        spirv::ExecutionModel::Vertex,
+        StageInterface::default(),
+    )
+    .unwrap();
+    match check_stage(
+        &fragment,
+        &StageEnvironment::default(),
+        "main",
+        spirv::ExecutionModel::Fragment,
+        interface,
+    ) {
+        Err(StageError::Input {
+            location,
+            error: InputError::UnmatchedLocation { .. },
+            ..
+        }) => assert!(unwritten.contains(&location)),
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_matrix_column_padding() {
+    let mut module = naga::Module::generate_empty();
+    let matrix = |module: &mut naga::Module, size| {
+        module.types.append(naga::Type {
+            name: None,
+            inner: naga::TypeInner::Matrix {
+                columns: size,
+                rows: size,
+                kind: naga::ScalarKind::Float,
+                width: 32,
+            },
+        })
+    };
+    let mat2 = matrix(&mut module, naga::VectorSize::Bi);
+    let mat3 = matrix(&mut module, naga::VectorSize::Tri);
+    let size = |ty, layout| get_aligned_type_size(&module, ty, layout, false).unwrap();
+    // Under std140 every column takes up a full 16 bytes.
+    assert_eq!(size(mat2, BufferLayout::Uniform), 32);
+    assert_eq!(size(mat3, BufferLayout::Uniform), 48);
+    assert_eq!(size(mat2, BufferLayout::Storage), 16);
+    assert_eq!(size(mat3, BufferLayout::Storage), 36);
+
+    add_global(
+        &mut module,
+        spirv::StorageClass::Uniform,
+        naga::Binding::Descriptor { set: 0, binding: 0 },
+        naga::TypeInner::Struct {
+            members: vec![naga::StructMember {
+                name: None,
+                binding: None,
+                ty: mat3,
+                offset: 0,
+            }],
+        },
+    );
+    let mut entries = BindEntryMap::default();
+    entries.insert(
+        0,
+        BindGroupLayoutEntry::new(
+            0,
+            wgt::ShaderStage::VERTEX,
+            BindingType::UniformBuffer {
+                dynamic: false,
+                min_binding_size: None,
+            },
+        ),
+    );
+    infer_min_binding_sizes(&mut entries, &module, 0);
+    match entries[&0].ty {
+        BindingType::UniformBuffer {
+            min_binding_size: Some(size),
+            ..
+        } => assert_eq!(size.get(), 48),
+        ref other => panic!("unexpected binding type: {:?}", other),
+    }
+}
+
+#[test]
+fn test_duplicate_output_location() {
+    let mut module = naga::Module::generate_empty();
+    for _ in 0..2 {
+        add_global(
+            &mut module,
+            spirv::StorageClass::Output,
+            naga::Binding::Location(0),
+            naga::TypeInner::Vector {
+                size: naga::VectorSize::Quad,
+                kind: naga::ScalarKind::Float,
+                width: 32,
+            },
+        );
+    }
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Vertex,
+        vec![naga::GlobalUse::STORE, naga::GlobalUse::STORE],
+    );
+    match check_stage(
+        &module,
+        &StageEnvironment::default(),
+        "main",
+        spirv::ExecutionModel::Vertex,
+        StageInterface::default(),
+    ) {
+        Err(StageError::DuplicateOutputLocation { location: 0 }) => {}
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_interface_fingerprint() {
+    let vec4 = naga::TypeInner::Vector {
+        size: naga::VectorSize::Quad,
+        kind: naga::ScalarKind::Float,
+        width: 32,
+    };
+    let sampler = |module: &mut naga::Module| {
+        add_global(
+            module,
+            spirv::StorageClass::UniformConstant,
+            naga::Binding::Descriptor { set: 0, binding: 1 },
+            naga::TypeInner::Sampler { comparison: false },
+        )
+    };
+    let output = |module: &mut naga::Module, inner: naga::TypeInner| {
+        add_global(
+            module,
+            spirv::StorageClass::Output,
+            naga::Binding::Location(0),
+            inner,
+        )
+    };
+    let fingerprint = |module: &naga::Module| {
+        interface_fingerprint(module, "main", spirv::ExecutionModel::Fragment).unwrap()
+    };
+
+    let mut first = naga::Module::generate_empty();
+    sampler(&mut first);
+    output(&mut first, vec4.clone());
+    add_entry_point(
+        &mut first,
+        spirv::ExecutionModel::Fragment,
+        vec![naga::GlobalUse::LOAD, naga::GlobalUse::STORE],
+    );
+
+    // Same interface, declared in a different order and with an unused global.
+    let mut second = naga::Module::generate_empty();
+    output(&mut second, vec4.clone());
+    output(&mut second, vec4.clone());
+    sampler(&mut second);
+    add_entry_point(
+        &mut second,
+        spirv::ExecutionModel::Fragment,
+        vec![
+            naga::GlobalUse::STORE,
+            naga::GlobalUse::empty(),
+            naga::GlobalUse::LOAD,
+        ],
+    );
+    assert_eq!(fingerprint(&first), fingerprint(&second));
+
+    let mut changed = naga::Module::generate_empty();
+    sampler(&mut changed);
+    output(
+        &mut changed,
+        naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Uint,
+            width: 32,
+        },
+    );
+    add_entry_point(
+        &mut changed,
+        spirv::ExecutionModel::Fragment,
+        vec![naga::GlobalUse::LOAD, naga::GlobalUse::STORE],
+    );
+    assert_ne!(fingerprint(&first), fingerprint(&changed));
+}
+
+#[test]
+fn test_buffer_usage_multi() {
+    use wgt::BufferUsage as Bu;
+    let actual = Bu::COPY_SRC | Bu::INDEX | Bu::VERTEX;
+    assert!(check_buffer_usage_multi(actual, &[Bu::COPY_SRC, Bu::INDEX]).is_ok());
+    assert!(check_buffer_usage(actual, Bu::COPY_SRC | Bu::VERTEX).is_ok());
+
+    let error = check_buffer_usage_multi(
+        Bu::COPY_SRC,
+        &[Bu::COPY_SRC, Bu::INDEX, Bu::INDIRECT | Bu::UNIFORM],
+    )
+    .unwrap_err();
+    assert_eq!(error.actual, Bu::COPY_SRC);
+    // Only the flags that are actually missing are reported.
+    assert_eq!(error.expected, Bu::INDEX | Bu::INDIRECT | Bu::UNIFORM);
+
+    let error = check_buffer_usage(Bu::COPY_SRC, Bu::COPY_SRC | Bu::INDEX).unwrap_err();
+    assert_eq!(error.expected, Bu::INDEX);
+}
+
+#[test]
+fn test_write_to_readonly_storage() {
+    let mut module = naga::Module::generate_empty();
+    let uint = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Scalar {
+            kind: naga::ScalarKind::Uint,
+            width: 32,
+        },
+    });
+    let var = add_global(
+        &mut module,
+        spirv::StorageClass::StorageBuffer,
+        naga::Binding::Descriptor { set: 0, binding: 0 },
+        naga::TypeInner::Struct {
+            members: vec![naga::StructMember {
+                name: None,
+                binding: None,
+                ty: uint,
+                offset: 0,
+            }],
+        },
+    );
+    let storage = |readonly| {
+        BindGroupLayoutEntry::new(
+            0,
+            wgt::ShaderStage::COMPUTE,
+            BindingType::StorageBuffer {
+                dynamic: false,
+                min_binding_size: None,
+                readonly,
+            },
+        )
+    };
+    let var = &module.global_variables[var];
+    let check = |entry: &BindGroupLayoutEntry, usage| {
+        check_binding(
+            &module,
+            var,
+            entry,
+            usage,
+            wgt::ShaderStage::COMPUTE,
+            wgt::Features::empty(),
+            &wgt::Limits::default(),
+        )
+    };
+    let load_store = naga::GlobalUse::LOAD | naga::GlobalUse::STORE;
+    assert!(check(&storage(false), load_store).is_ok());
+    assert!(check(&storage(true), naga::GlobalUse::LOAD).is_ok());
+    assert!(matches!(
+        check(&storage(true), load_store),
+        Err(BindingError::WriteToReadonlyStorage)
+    ));
+    // Other mismatches still report the generic usage error.
+    let uniform = BindGroupLayoutEntry::new(
+        0,
+        wgt::ShaderStage::COMPUTE,
+        BindingType::UniformBuffer {
+            dynamic: false,
+            min_binding_size: None,
+        },
+    );
+    let error = check(&uniform, load_store).unwrap_err();
+    match error {
+        BindingError::WrongUsage { used, allowed } => {
+            assert_eq!(used, load_store);
+            assert_eq!(allowed, naga::GlobalUse::LOAD);
+        }
+        ref other => panic!("unexpected error: {:?}", other),
+    }
+    let message = error.to_string();
+    assert!(message.contains(&format!("uses {:?}", load_store)));
+    assert!(message.contains(&format!("only allows {:?}", naga::GlobalUse::LOAD)));
+}
+
+#[test]
+fn test_shared_exponent_format() {
+    match map_texture_format(wgt::TextureFormat::Rgb9e5Ufloat) {
+        naga::TypeInner::Vector {
+            size: naga::VectorSize::Tri,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        } => {}
+        other => panic!("unexpected type: {:?}", other),
+    }
+    assert_eq!(
+        wgt::TextureComponentType::from(wgt::TextureFormat::Rgb9e5Ufloat),
+        wgt::TextureComponentType::Float
+    );
+
+    let mut module = naga::Module::generate_empty();
+    let texel = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        },
+    });
+    let result = check_single_binding(
+        &mut module,
+        naga::TypeInner::Image {
+            base: texel,
+            dim: spirv::Dim::Dim2D,
+            flags: naga::ImageFlags::empty(),
+        },
+        BindingType::StorageTexture {
+            dimension: wgt::TextureViewDimension::D2,
+            format: wgt::TextureFormat::Rgb9e5Ufloat,
+            readonly: true,
+        },
+        naga::GlobalUse::LOAD,
+    );
+    assert!(matches!(
+        result,
+        Err(BindingError::UnsupportedStorageTextureFormat(
+            wgt::TextureFormat::Rgb9e5Ufloat
+        ))
+    ));
+}
+
+#[test]
+fn test_unused_bindings() {
+    let mut module = naga::Module::generate_empty();
+    add_global(
+        &mut module,
+        spirv::StorageClass::UniformConstant,
+        naga::Binding::Descriptor { set: 0, binding: 0 },
+        naga::TypeInner::Sampler { comparison: false },
+    );
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Fragment,
+        vec![naga::GlobalUse::empty()],
+    );
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Vertex,
+        vec![naga::GlobalUse::LOAD],
+    );
+    let entry = |binding, ty| BindGroupLayoutEntry::new(binding, wgt::ShaderStage::all(), ty);
+    let mut samplers = BindEntryMap::default();
+    samplers.insert(0, entry(0, BindingType::Sampler { comparison: false }));
+    let mut uniforms = BindEntryMap::default();
+    uniforms.insert(
+        2,
+        entry(
+            2,
+            BindingType::UniformBuffer {
+                dynamic: false,
+                min_binding_size: None,
+            },
+        ),
+    );
+    // The sampler is used by the vertex entry point only, which is enough.
+    assert!(unused_bindings(&module, &[&samplers]).is_empty());
+    assert_eq!(
+        unused_bindings(&module, &[&samplers, &uniforms]),
+        vec![(1, 2)]
+    );
+}
+
+#[test]
+fn test_multisampled_array() {
+    let mut module = naga::Module::generate_empty();
+    let texel = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        },
+    });
+    let mut check = |flags, dimension| {
+        check_single_binding(
+            &mut module,
+            naga::TypeInner::Image {
+                base: texel,
+                dim: spirv::Dim::Dim2D,
+                flags: naga::ImageFlags::SAMPLED | naga::ImageFlags::MULTISAMPLED | flags,
+            },
+            BindingType::SampledTexture {
+                dimension,
+                component_type: wgt::TextureComponentType::Float,
+                multisampled: true,
+            },
+            naga::GlobalUse::LOAD,
+        )
+    };
+    assert!(check(naga::ImageFlags::empty(), wgt::TextureViewDimension::D2).is_ok());
+    assert!(matches!(
+        check(
+            naga::ImageFlags::ARRAYED,
+            wgt::TextureViewDimension::D2Array
+        ),
+        Err(BindingError::MultisampledArrayUnsupported)
+    ));
+}
+
+#[test]
+fn test_vertex_format_normalization() {
+    let vertex_module = |kind| {
+        let mut module = naga::Module::generate_empty();
+        add_global(
+            &mut module,
+            spirv::StorageClass::Input,
+            naga::Binding::Location(0),
+            naga::TypeInner::Vector {
+                size: naga::VectorSize::Quad,
+                kind,
+                width: 32,
+            },
+        );
+        add_entry_point(
+            &mut module,
+            spirv::ExecutionModel::Vertex,
+            vec![naga::GlobalUse::LOAD],
+        );
+        module
+    };
+    let check = |module: &naga::Module, format| {
+        let attributes = [wgt::VertexAttributeDescriptor {
+            offset: 0,
+            format,
+            shader_location: 0,
+        }];
+        let buffers = [wgt::VertexBufferDescriptor {
+            stride: 4,
+            step_mode: wgt::InputStepMode::Vertex,
+            attributes: &attributes,
+        }];
+        check_stage(
+            module,
+            &StageEnvironment::default(),
+            "main",
+            spirv::ExecutionModel::Vertex,
+            vertex_buffer_interface(&buffers, wgt::Features::empty()).unwrap(),
+        )
+        .err()
+    };
+    let float_input = vertex_module(naga::ScalarKind::Float);
+    let uint_input = vertex_module(naga::ScalarKind::Uint);
+
+    assert!(check(&float_input, wgt::VertexFormat::Uchar4Norm).is_none());
+    match check(&float_input, wgt::VertexFormat::Uchar4) {
+        Some(StageError::Input {
+            location: 0,
+            error: InputError::WrongType,
+            ..
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(check(&uint_input, wgt::VertexFormat::Uchar4).is_none());
+    match check(&uint_input, wgt::VertexFormat::Uchar4Norm) {
+        Some(StageError::Input {
+            location: 0,
+            error: InputError::WrongType,
+            ..
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_partial_array_element() {
+    let mut module = naga::Module::generate_empty();
+    let vec4 = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        },
+    });
+    let lights = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Array {
+            base: vec4,
+            size: naga::ArraySize::Dynamic,
+            stride: None,
+        },
+    });
+    let member = |ty, offset| naga::StructMember {
+        name: None,
+        binding: None,
+        ty,
+        offset,
+    };
+    let mut check = |size| {
+        check_single_binding(
+            &mut module,
+            naga::TypeInner::Struct {
+                members: vec![member(vec4, 0), member(lights, 16)],
+            },
+            BindingType::StorageBuffer {
+                dynamic: false,
+                min_binding_size: wgt::BufferSize::new(size),
+                readonly: true,
+            },
+            naga::GlobalUse::LOAD,
+        )
+    };
+    // A 16 byte header followed by two lights.
+    assert!(check(48).is_ok());
+    // Two and a half lights.
+    assert!(matches!(
+        check(56),
+        Err(BindingError::PartialArrayElement {
+            stride: 16,
+            remainder: 8,
+        })
+    ));
+}
+
+#[test]
+fn test_padded_array_header() {
+    let mut module = naga::Module::generate_empty();
+    let uint = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Scalar {
+            kind: naga::ScalarKind::Uint,
+            width: 32,
+        },
+    });
+    let vec4 = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        },
+    });
+    let array = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Array {
+            base: vec4,
+            size: naga::ArraySize::Dynamic,
+            stride: None,
+        },
+    });
+    let member = |ty, offset| naga::StructMember {
+        name: None,
+        binding: None,
+        ty,
+        offset,
+    };
+    let var = add_global(
+        &mut module,
+        spirv::StorageClass::StorageBuffer,
+        naga::Binding::Descriptor { set: 0, binding: 0 },
+        naga::TypeInner::Struct {
+            members: vec![member(uint, 0), member(array, 16)],
+        },
+    );
+    let entry = BindGroupLayoutEntry::new(
+        0,
+        wgt::ShaderStage::COMPUTE,
+        BindingType::StorageBuffer {
+            dynamic: false,
+            min_binding_size: wgt::BufferSize::new(48),
+            readonly: true,
+        },
+    );
+    // The header is padded to 16 bytes, which leaves room for two elements.
+    assert!(check_binding(
+        &module,
+        &module.global_variables[var],
+        &entry,
+        naga::GlobalUse::LOAD,
+        wgt::ShaderStage::COMPUTE,
+        wgt::Features::empty(),
+        &wgt::Limits::default(),
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_struct_size_padding() {
+    let mut module = naga::Module::generate_empty();
+    let uint = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Scalar {
+            kind: naga::ScalarKind::Uint,
+            width: 32,
+        },
+    });
+    let vec4 = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        },
+    });
+    let members = vec![
+        naga::StructMember {
+            name: None,
+            binding: None,
+            ty: uint,
+            offset: 0,
+        },
+        naga::StructMember {
+            name: None,
+            binding: None,
+            ty: vec4,
+            offset: 16,
+        },
+    ];
+    let ty = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Struct {
+            members: members.clone(),
+        },
+    });
+    // The padding between the members counts towards the size.
+    assert_eq!(
+        get_struct_size(&module, &members, BufferLayout::Storage).unwrap(),
+        32
+    );
+    assert_eq!(type_size(&module, ty).unwrap(), 32);
+}
+
+#[test]
+fn test_available_entry_points() {
+    let mut module = naga::Module::generate_empty();
+    add_entry_point(&mut module, spirv::ExecutionModel::Vertex, Vec::new());
+    add_entry_point(&mut module, spirv::ExecutionModel::Fragment, Vec::new());
+    let expected = vec![
+        ("main".to_string(), spirv::ExecutionModel::Vertex),
+        ("main".to_string(), spirv::ExecutionModel::Fragment),
+    ];
+    assert_eq!(available_entry_points(&module), expected);
+
+    let error = check_stage(
+        &module,
+        &StageEnvironment::default(),
+        "main",
+        spirv::ExecutionModel::GLCompute,
+        StageInterface::default(),
+    )
+    .err()
+    .unwrap();
+    match error {
+        StageError::MissingEntryPoint { ref available, .. } => assert_eq!(*available, expected),
+        ref other => panic!("unexpected error: {:?}", other),
+    }
+    let message = error.to_string();
+    assert!(message.contains("Vertex"));
+    assert!(message.contains("Fragment"));
+}
+
+#[test]
+fn test_matrix_width_mismatch() {
+    let mat4 = |width| naga::TypeInner::Matrix {
+        columns: naga::VectorSize::Quad,
+        rows: naga::VectorSize::Quad,
+        kind: naga::ScalarKind::Float,
+        width,
+    };
+    assert!(is_sub_type(&mat4(32), &mat4(32), None, WidthRule::Narrow));
+    // Unlike scalars and vectors, matrices aren't promoted to a wider type.
+    assert!(!is_sub_type(&mat4(32), &mat4(16), None, WidthRule::Narrow));
+    assert!(!is_sub_type(&mat4(16), &mat4(32), None, WidthRule::Narrow));
+
+    let mut module = naga::Module::generate_empty();
+    add_global(
+        &mut module,
+        spirv::StorageClass::Input,
+        naga::Binding::Location(0),
+        mat4(32),
+    );
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Fragment,
+        vec![naga::GlobalUse::LOAD],
+    );
+    let outputs = iter::once((0, MaybeOwned::Owned(mat4(16)))).collect();
+    match check_stage(
+        &module,
+        &StageEnvironment::default(),
+        "main",
+        spirv::ExecutionModel::Fragment,
+        outputs,
+    ) {
+        Err(StageError::Input {
+            location: 0,
+            error: InputError::WrongType,
+            ..
+        }) => {}
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_unmatched_input_location() {
+    let mut module = naga::Module::generate_empty();
+    let float = naga::TypeInner::Scalar {
+        kind: naga::ScalarKind::Float,
+        width: 32,
+    };
+    add_global(
+        &mut module,
+        spirv::StorageClass::Input,
+        naga::Binding::Location(5),
+        float.clone(),
+    );
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Fragment,
+        vec![naga::GlobalUse::LOAD],
+    );
+    let check = |outputs| {
+        check_stage(
+            &module,
+            &StageEnvironment::default(),
+            "main",
+            spirv::ExecutionModel::Fragment,
+            outputs,
+        )
+        .err()
+    };
+    let outputs = (0..3)
+        .rev()
+        .map(|location| (location, MaybeOwned::Owned(float.clone())))
+        .collect();
+    match check(outputs) {
+        Some(StageError::Input {
+            location: 5,
+            error: InputError::UnmatchedLocation { ref available },
+            ..
+        }) => assert_eq!(*available, vec![0, 1, 2]),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    // Without any outputs the input is simply missing.
+    match check(StageInterface::default()) {
+        Some(StageError::Input {
+            location: 5,
+            error: InputError::Missing,
+            ..
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_half_input_widths() {
+    let float = |width| naga::TypeInner::Vector {
+        size: naga::VectorSize::Bi,
+        kind: naga::ScalarKind::Float,
+        width,
+    };
+    assert!(is_sub_type(&float(32), &float(16), None, WidthRule::Expand));
+    assert!(!is_sub_type(&float(32), &float(16), None, WidthRule::Exact));
+    assert!(is_sub_type(&float(16), &float(16), None, WidthRule::Exact));
+
+    let check = |execution_model| {
+        let mut module = naga::Module::generate_empty();
+        add_global(
+            &mut module,
+            spirv::StorageClass::Input,
+            naga::Binding::Location(0),
+            float(32),
+        );
+        add_entry_point(&mut module, execution_model, vec![naga::GlobalUse::LOAD]);
+        let inputs = iter::once((0, MaybeOwned::Owned(float(16)))).collect();
+        check_stage(
+            &module,
+            &StageEnvironment::default(),
+            "main",
+            execution_model,
+            inputs,
+        )
+        .err()
+    };
+    // A half attribute is expanded into the float the vertex shader reads.
+    assert!(check(spirv::ExecutionModel::Vertex).is_none());
+    // A half varying doesn't satisfy a float fragment input.
+    match check(spirv::ExecutionModel::Fragment) {
+        Some(StageError::Input {
+            location: 0,
+            error: InputError::WrongType,
+            ..
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_stage_validation_key() {
+    let mut module = naga::Module::generate_empty();
+    let float4 = naga::TypeInner::Vector {
+        size: naga::VectorSize::Quad,
+        kind: naga::ScalarKind::Float,
+        width: 32,
+    };
+    add_global(
+        &mut module,
+        spirv::StorageClass::Input,
+        naga::Binding::Location(0),
+        float4.clone(),
+    );
+    add_global(
+        &mut module,
+        spirv::StorageClass::Output,
+        naga::Binding::Location(1),
+        float4.clone(),
+    );
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Vertex,
+        vec![naga::GlobalUse::LOAD, naga::GlobalUse::STORE],
+    );
+    let inputs = || iter::once((0, MaybeOwned::Owned(float4.clone()))).collect::<StageInterface>();
+    let key = |features, inputs: &StageInterface| {
+        StageValidationKey::new(
+            &module,
+            &StageEnvironment {
+                features,
+                ..StageEnvironment::default()
+            },
+            "main",
+            spirv::ExecutionModel::Vertex,
+            inputs,
+        )
+        .unwrap()
+    };
+    let validate = || {
+        let outputs = check_stage(
+            &module,
+            &StageEnvironment::default(),
+            "main",
+            spirv::ExecutionModel::Vertex,
+            inputs(),
+        )
+        .unwrap();
+        outputs
+            .iter()
+            .map(|(&location, ty)| (location, format!("{:?}", **ty)))
+            .collect::<Vec<_>>()
+    };
+
+    let first = validate();
+    assert_eq!(first.len(), 1);
+    assert_eq!(first, validate());
+    assert_eq!(
+        key(wgt::Features::empty(), &inputs()),
+        key(wgt::Features::empty(), &inputs())
+    );
+    assert_ne!(
+        key(wgt::Features::empty(), &inputs()),
+        key(wgt::Features::PUSH_CONSTANTS, &inputs())
+    );
+    assert_ne!(
+        key(wgt::Features::empty(), &inputs()),
+        key(wgt::Features::empty(), &StageInterface::default())
+    );
+}
+
+#[test]
+fn test_storage_texel_components() {
+    let check = |format, size| {
+        let mut module = naga::Module::generate_empty();
+        let texel = module.types.append(naga::Type {
+            name: None,
+            inner: naga::TypeInner::Vector {
+                size,
+                kind: naga::ScalarKind::Float,
+                width: 32,
+            },
+        });
+        check_single_binding(
+            &mut module,
+            naga::TypeInner::Image {
+                base: texel,
+                dim: spirv::Dim::Dim2D,
+                flags: naga::ImageFlags::empty(),
+            },
+            BindingType::StorageTexture {
+                dimension: wgt::TextureViewDimension::D2,
+                format,
+                readonly: false,
+            },
+            naga::GlobalUse::STORE,
+        )
+    };
+    assert!(check(wgt::TextureFormat::Rgba8Unorm, naga::VectorSize::Quad).is_ok());
+    assert!(check(wgt::TextureFormat::Rg32Float, naga::VectorSize::Bi).is_ok());
+    assert!(matches!(
+        check(wgt::TextureFormat::Rgba8Unorm, naga::VectorSize::Bi),
+        Err(BindingError::WrongStorageTexel {
+            expected: naga::TypeInner::Vector {
+                size: naga::VectorSize::Quad,
+                ..
+            },
+            actual: naga::TypeInner::Vector {
+                size: naga::VectorSize::Bi,
+                ..
+            },
+        })
+    ));
+    // The component kind has to match as well.
+    assert!(matches!(
+        check(wgt::TextureFormat::Rgba8Uint, naga::VectorSize::Quad),
+        Err(BindingError::WrongStorageTexel { .. })
+    ));
+}
+
+#[test]
+fn test_reflected_interface_round_trip() {
+    let float4 = naga::TypeInner::Vector {
+        size: naga::VectorSize::Quad,
+        kind: naga::ScalarKind::Float,
+        width: 32,
+    };
+    let uint = naga::TypeInner::Scalar {
+        kind: naga::ScalarKind::Uint,
+        width: 32,
+    };
+    let module_with = |class| {
+        let mut module = naga::Module::generate_empty();
+        add_global(
+            &mut module,
+            class,
+            naga::Binding::Location(0),
+            float4.clone(),
+        );
+        add_global(&mut module, class, naga::Binding::Location(3), uint.clone());
+        module
+    };
+
+    let reflected = {
+        let mut vertex = module_with(spirv::StorageClass::Output);
+        add_entry_point(
+            &mut vertex,
+            spirv::ExecutionModel::Vertex,
+            vec![naga::GlobalUse::STORE; 2],
+        );
+        let outputs = check_stage(
+            &vertex,
+            &StageEnvironment::default(),
+            "main",
+            spirv::ExecutionModel::Vertex,
+            StageInterface::default(),
+        )
+        .unwrap();
+        to_reflected(&outputs)
+    };
+    assert_eq!(reflected.varyings.len(), 2);
+    assert_eq!(reflected.varyings[0].0, 0);
+    assert_eq!(reflected.varyings[0].1.components, 4);
+    assert_eq!(reflected.varyings[1].0, 3);
+    assert_eq!(reflected.varyings[1].1.kind, ReflectedScalarKind::Uint);
+
+    let mut fragment = module_with(spirv::StorageClass::Input);
+    add_entry_point(
+        &mut fragment,
+        spirv::ExecutionModel::Fragment,
+        vec![naga::GlobalUse::LOAD; 2],
+    );
+    let result = check_stage(
+        &fragment,
+        &StageEnvironment::default(),
+        "main",
+        spirv::ExecutionModel::Fragment,
+        reflected.to_stage_interface(),
+    );
+    assert!(result.is_ok());
+    assert_eq!(to_reflected(&reflected.to_stage_interface()), reflected);
+}
+
+#[test]
+fn test_workgroup_storage_limit() {
+    let mut module = naga::Module::generate_empty();
+    let float = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Scalar {
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        },
+    });
+    let shared = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Array {
+            base: float,
+            size: naga::ArraySize::Static(8192),
+            stride: None,
+        },
+    });
+    module.global_variables.append(naga::GlobalVariable {
+        name: None,
+        class: spirv::StorageClass::Workgroup,
+        binding: None,
+        ty: shared,
+    });
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::GLCompute,
+        vec![naga::GlobalUse::LOAD | naga::GlobalUse::STORE],
+    );
+    let check = |limit| {
+        let limits = wgt::Limits {
+            max_compute_workgroup_storage_size: limit,
+            ..wgt::Limits::default()
+        };
+        check_stage(
+            &module,
+            &StageEnvironment {
+                limits,
+                ..StageEnvironment::default()
+            },
+            "main",
+            spirv::ExecutionModel::GLCompute,
+            StageInterface::default(),
+        )
+    };
+    match check(16384) {
+        Err(StageError::WorkgroupStorageExceeded {
+            used: 32768,
+            limit: 16384,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other.err()),
+    }
+    assert!(check(32768).is_ok());
+}
+
+#[test]
+fn test_core_vertex_formats() {
+    use wgt::VertexFormat as Vf;
+    let formats = [
+        Vf::Uchar2,
+        Vf::Char4Norm,
+        Vf::Ushort2Norm,
+        Vf::Half4,
+        Vf::Float,
+        Vf::Float3,
+        Vf::Uint4,
+        Vf::Int2,
+    ];
+    for &format in formats.iter() {
+        assert_eq!(
+            map_vertex_format(format, wgt::Features::empty()).unwrap(),
+            vertex_format_type(format)
+        );
+    }
+}
+
+#[test]
+fn test_double_vertex_format_feature() {
+    assert!(matches!(
+        map_vertex_format(wgt::VertexFormat::Double2, wgt::Features::empty()),
+        Err(VertexFormatError::MissingFeature {
+            format: wgt::VertexFormat::Double2,
+            feature: wgt::Features::VERTEX_ATTRIBUTE_64BIT,
+        })
+    ));
+    assert_eq!(
+        map_vertex_format(
+            wgt::VertexFormat::Double2,
+            wgt::Features::VERTEX_ATTRIBUTE_64BIT
+        )
+        .unwrap(),
+        naga::TypeInner::Vector {
+            size: naga::VectorSize::Bi,
+            kind: naga::ScalarKind::Float,
+            width: 64,
+        }
+    );
+}
+
+#[test]
+fn test_blend_compatibility() {
+    let alpha_blending = wgt::BlendDescriptor {
+        src_factor: wgt::BlendFactor::SrcAlpha,
+        dst_factor: wgt::BlendFactor::OneMinusSrcAlpha,
+        operation: wgt::BlendOperation::Add,
+    };
+    assert!(check_blend_compatibility(wgt::TextureFormat::Rgba8Unorm, &alpha_blending).is_ok());
+    assert!(check_blend_compatibility(wgt::TextureFormat::Rg8Unorm, &alpha_blending).is_ok());
+    let dst_alpha_blending = wgt::BlendDescriptor {
+        src_factor: wgt::BlendFactor::One,
+        dst_factor: wgt::BlendFactor::OneMinusDstAlpha,
+        operation: wgt::BlendOperation::Add,
+    };
+    assert!(check_blend_compatibility(wgt::TextureFormat::Rgba8Unorm, &dst_alpha_blending).is_ok());
+    assert!(matches!(
+        check_blend_compatibility(wgt::TextureFormat::Rg8Unorm, &dst_alpha_blending),
+        Err(BlendError::AlphaFactorWithoutAlpha {
+            format: wgt::TextureFormat::Rg8Unorm,
+            factor: wgt::BlendFactor::OneMinusDstAlpha,
+        })
+    ));
+    assert!(check_blend_compatibility(
+        wgt::TextureFormat::Rg8Unorm,
+        &wgt::BlendDescriptor::REPLACE
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_struct_interface_member_names() {
+    let float_struct = |module: &mut naga::Module, name: &str| {
+        let float = module.types.append(naga::Type {
+            name: None,
+            inner: naga::TypeInner::Scalar {
+                kind: naga::ScalarKind::Float,
+                width: 32,
+            },
+        });
+        naga::TypeInner::Struct {
+            members: vec![naga::StructMember {
+                name: Some(name.to_string()),
+                binding: None,
+                ty: float,
+                offset: 0,
+            }],
+        }
+    };
+
+    let mut vertex = naga::Module::generate_empty();
+    let output = float_struct(&mut vertex, "color");
+    add_global(
+        &mut vertex,
+        spirv::StorageClass::Output,
+        naga::Binding::Location(0),
+        output,
+    );
+    add_entry_point(
+        &mut vertex,
+        spirv::ExecutionModel::Vertex,
+        vec![naga::GlobalUse::STORE],
+    );
+    let mut fragment = naga::Module::generate_empty();
+    let input = float_struct(&mut fragment, "tint");
+    add_global(
+        &mut fragment,
+        spirv::StorageClass::Input,
+        naga::Binding::Location(0),
+        input,
+    );
+    add_entry_point(
+        &mut fragment,
+        spirv::ExecutionModel::Fragment,
+        vec![naga::GlobalUse::LOAD],
+    );
+
+    let outputs = check_stage(
+        &vertex,
+        &StageEnvironment::default(),
+        "main",
+        spirv::ExecutionModel::Vertex,
+        StageInterface::default(),
+    )
+    .unwrap();
+    let result = check_stage(
+        &fragment,
+        &StageEnvironment::default(),
+        "main",
+        spirv::ExecutionModel::Fragment,
+        outputs,
+    );
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_depth_stencil_format() {
+    assert!(
+        check_depth_stencil_format(Some(wgt::TextureFormat::Depth24PlusStencil8), true, true)
+            .is_ok()
+    );
+    match check_depth_stencil_format(Some(wgt::TextureFormat::Rgba8Unorm), true, false) {
+        Err(DepthStencilError::NotDepthStencilFormat(wgt::TextureFormat::Rgba8Unorm)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match check_depth_stencil_format(Some(wgt::TextureFormat::Depth32Float), false, true) {
+        Err(DepthStencilError::MissingStencilAspect(wgt::TextureFormat::Depth32Float)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match check_depth_stencil_format(None, true, false) {
+        Err(DepthStencilError::MissingDepthAttachment) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(check_depth_stencil_format(None, false, false).is_ok());
+
+    let mut module = naga::Module::generate_empty();
+    add_global(
+        &mut module,
+        spirv::StorageClass::Output,
+        naga::Binding::BuiltIn(naga::BuiltIn::FragDepth),
+        naga::TypeInner::Scalar {
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        },
+    );
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Fragment,
+        vec![naga::GlobalUse::STORE],
+    );
+    assert!(writes_frag_depth(&module, "main").unwrap());
+}
+
+#[test]
+fn test_wrong_texture_component_type() {
+    let check = |base_inner| {
+        let mut module = naga::Module::generate_empty();
+        let base = module.types.append(naga::Type {
+            name: None,
+            inner: base_inner,
+        });
+        check_single_binding(
+            &mut module,
+            naga::TypeInner::Image {
+                base,
+                dim: spirv::Dim::Dim2D,
+                flags: naga::ImageFlags::SAMPLED,
+            },
+            BindingType::SampledTexture {
+                dimension: wgt::TextureViewDimension::D2,
+                component_type: wgt::TextureComponentType::Float,
+                multisampled: false,
+            },
+            naga::GlobalUse::LOAD,
+        )
+    };
+    assert!(matches!(
+        check(naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Uint,
+            width: 32,
+        }),
+        Err(BindingError::WrongTextureComponentType {
+            expected: wgt::TextureComponentType::Float,
+            actual_kind: Some(naga::ScalarKind::Uint),
+        })
+    ));
+    assert!(matches!(
+        check(naga::TypeInner::Matrix {
+            columns: naga::VectorSize::Quad,
+            rows: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        }),
+        Err(BindingError::WrongTextureComponentType {
+            expected: wgt::TextureComponentType::Float,
+            actual_kind: None,
+        })
+    ));
+}
+
+#[test]
+fn test_uniform_buffer_too_large() {
+    let mut module = naga::Module::generate_empty();
+    let vec4 = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        },
+    });
+    let members = (0..8)
+        .map(|i| naga::StructMember {
+            name: None,
+            binding: None,
+            ty: vec4,
+            offset: i * 16,
+        })
+        .collect();
+    let var = add_global(
+        &mut module,
+        spirv::StorageClass::Uniform,
+        naga::Binding::Descriptor { set: 0, binding: 0 },
+        naga::TypeInner::Struct { members },
+    );
+    let limits = wgt::Limits {
+        max_uniform_buffer_binding_size: 64,
+        ..wgt::Limits::default()
+    };
+    let check = |ty| {
+        let entry = BindGroupLayoutEntry::new(0, wgt::ShaderStage::VERTEX, ty);
+        check_binding(
+            &module,
+            &module.global_variables[var],
+            &entry,
+            naga::GlobalUse::LOAD,
+            wgt::ShaderStage::VERTEX,
+            wgt::Features::empty(),
+            &limits,
+        )
+    };
+
+    match check(BindingType::UniformBuffer {
+        dynamic: false,
+        min_binding_size: None,
+    }) {
+        Err(BindingError::UniformBufferTooLarge {
+            size: 128,
+            limit: 64,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+    // The limit only applies to uniform buffers.
+    assert!(check(BindingType::StorageBuffer {
+        dynamic: false,
+        min_binding_size: None,
+        readonly: true,
+    })
+    .is_ok());
+}
+
+#[test]
+fn test_texel_buffer() {
+    let mut module = naga::Module::generate_empty();
+    let texel = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Scalar {
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        },
+    });
+    let var = add_global(
+        &mut module,
+        spirv::StorageClass::UniformConstant,
+        naga::Binding::Descriptor { set: 0, binding: 0 },
+        naga::TypeInner::Image {
+            base: texel,
+            dim: spirv::Dim::DimBuffer,
+            flags: naga::ImageFlags::empty(),
+        },
+    );
+    let check = |format, features| {
+        let entry = BindGroupLayoutEntry::new(
+            0,
+            wgt::ShaderStage::COMPUTE,
+            BindingType::StorageTexelBuffer {
+                format,
+                readonly: false,
+            },
+        );
+        check_binding(
+            &module,
+            &module.global_variables[var],
+            &entry,
+            naga::GlobalUse::LOAD | naga::GlobalUse::STORE,
+            wgt::ShaderStage::COMPUTE,
+            features,
+            &wgt::Limits::default(),
+        )
+    };
+
+    assert!(check(wgt::TextureFormat::R32Float, wgt::Features::TEXEL_BUFFERS).is_ok());
+    assert!(matches!(
+        check(wgt::TextureFormat::R32Float, wgt::Features::empty()),
+        Err(BindingError::MissingFeature(wgt::Features::TEXEL_BUFFERS))
+    ));
+    assert!(matches!(
+        check(
+            wgt::TextureFormat::Rgba32Float,
+            wgt::Features::TEXEL_BUFFERS
+        ),
+        Err(BindingError::WrongStorageTexel { .. })
+    ));
+}
+
+#[test]
+fn test_too_few_bind_groups() {
+    let mut module = naga::Module::generate_empty();
+    add_global(
+        &mut module,
+        spirv::StorageClass::UniformConstant,
+        naga::Binding::Descriptor { set: 3, binding: 0 },
+        naga::TypeInner::Sampler { comparison: false },
+    );
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Fragment,
+        vec![naga::GlobalUse::LOAD],
+    );
+    let entries = BindEntryMap::default();
+    match check_stage(
+        &module,
+        &StageEnvironment {
+            group_layouts: &[&entries, &entries],
+            ..StageEnvironment::default()
+        },
+        "main",
+        spirv::ExecutionModel::Fragment,
+        StageInterface::default(),
+    ) {
+        Err(StageError::TooFewBindGroups {
+            required: 4,
+            provided: 2,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other.err()),
+    }
+}
+
+#[test]
+fn test_maybe_owned_clone() {
+    let ty = naga::TypeInner::Scalar {
+        kind: naga::ScalarKind::Float,
+        width: 32,
+    };
+    let borrowed = MaybeOwned::Borrowed(&ty);
+    let owned = MaybeOwned::Owned(ty.clone());
+    for value in [borrowed, owned].iter() {
+        let clone = value.clone();
+        match clone {
+            MaybeOwned::Owned(_) => {}
+            MaybeOwned::Borrowed(_) => panic!("clone is still borrowed"),
+        }
+        assert_eq!(*clone, **value);
+        assert_eq!(clone.into_owned(), ty);
+    }
+}
+
+#[test]
+fn test_writable_storage_in_vertex_stage() {
+    let mut module = naga::Module::generate_empty();
+    add_global(
+        &mut module,
+        spirv::StorageClass::StorageBuffer,
+        naga::Binding::Descriptor { set: 0, binding: 0 },
+        naga::TypeInner::Struct {
+            members: Vec::new(),
+        },
+    );
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Vertex,
+        vec![naga::GlobalUse::STORE],
+    );
+    let mut entries = BindEntryMap::default();
+    entries.insert(
+        0,
+        BindGroupLayoutEntry::new(
+            0,
+            wgt::ShaderStage::VERTEX,
+            BindingType::StorageBuffer {
+                dynamic: false,
+                min_binding_size: None,
+                readonly: false,
+            },
+        ),
+    );
+    let check = |features| {
+        check_stage(
+            &module,
+            &StageEnvironment {
+                group_layouts: &[&entries],
+                features,
+                ..StageEnvironment::default()
+            },
+            "main",
+            spirv::ExecutionModel::Vertex,
+            StageInterface::default(),
+        )
+    };
+
+    match check(wgt::Features::empty()) {
+        Err(StageError::Binding {
+            error: BindingError::WritableStorageInVertexStage,
+            ..
+        }) => {}
+        other => panic!("unexpected result: {:?}", other.err()),
+    }
+    assert!(check(wgt::Features::VERTEX_WRITABLE_STORAGE).is_ok());
+}
+
+#[test]
+fn test_derive_bind_group_layout_entries() {
+    let mut module = naga::Module::generate_empty();
+    let vec4 = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        },
+    });
+    add_global(
+        &mut module,
+        spirv::StorageClass::Uniform,
+        naga::Binding::Descriptor { set: 0, binding: 0 },
+        naga::TypeInner::Struct {
+            members: vec![naga::StructMember {
+                name: None,
+                binding: None,
+                ty: vec4,
+                offset: 0,
+            }],
+        },
+    );
+    add_global(
+        &mut module,
+        spirv::StorageClass::UniformConstant,
+        naga::Binding::Descriptor { set: 1, binding: 0 },
+        naga::TypeInner::Sampler { comparison: false },
+    );
+    add_global(
+        &mut module,
+        spirv::StorageClass::UniformConstant,
+        naga::Binding::Descriptor { set: 1, binding: 1 },
+        naga::TypeInner::Image {
+            base: vec4,
+            dim: spirv::Dim::Dim2D,
+            flags: naga::ImageFlags::SAMPLED,
+        },
+    );
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Fragment,
+        vec![naga::GlobalUse::LOAD; 3],
+    );
+
+    let sets = derive_bind_group_layout_entries(&module, wgt::ShaderStage::FRAGMENT);
+    assert_eq!(sets.len(), 2);
+    assert_eq!(sets[&0].len(), 1);
+    assert_eq!(sets[&1].len(), 2);
+    assert_eq!(
+        sets[&0][&0],
+        BindGroupLayoutEntry::new(
+            0,
+            wgt::ShaderStage::FRAGMENT,
+            BindingType::UniformBuffer {
+                dynamic: false,
+                min_binding_size: wgt::BufferSize::new(16),
+            },
+        )
+    );
+    assert_eq!(sets[&1][&0].ty, BindingType::Sampler { comparison: false });
+    assert_eq!(
+        sets[&1][&1].ty,
+        BindingType::SampledTexture {
+            dimension: wgt::TextureViewDimension::D2,
+            component_type: wgt::TextureComponentType::Float,
+            multisampled: false,
+        }
+    );
+    // The derived layout validates against the module it came from.
+    let layouts = [&sets[&0], &sets[&1]];
+    assert!(check_stage(
+        &module,
+        &StageEnvironment {
+            group_layouts: &layouts,
+            ..StageEnvironment::default()
+        },
+        "main",
+        spirv::ExecutionModel::Fragment,
+        StageInterface::default(),
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_too_many_inter_stage_components() {
+    let mut module = naga::Module::generate_empty();
+    for location in 0..4 {
+        add_global(
+            &mut module,
+            spirv::StorageClass::Output,
+            naga::Binding::Location(location),
+            naga::TypeInner::Vector {
+                size: naga::VectorSize::Quad,
+                kind: naga::ScalarKind::Float,
+                width: 32,
+            },
+        );
+    }
+    // The members of a structure count towards the limit as well.
+    let vec2 = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Vector {
+            size: naga::VectorSize::Bi,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        },
+    });
+    let vec4 = module.types.append(naga::Type {
+        name: None,
+        inner: naga::TypeInner::Vector {
+            size: naga::VectorSize::Quad,
+            kind: naga::ScalarKind::Float,
+            width: 32,
+        },
+    });
+    add_global(
+        &mut module,
+        spirv::StorageClass::Output,
+        naga::Binding::Location(4),
+        naga::TypeInner::Struct {
+            members: vec![
+                naga::StructMember {
+                    name: None,
+                    binding: None,
+                    ty: vec4,
+                    offset: 0,
+                },
+                naga::StructMember {
+                    name: None,
+                    binding: None,
+                    ty: vec2,
+                    offset: 16,
+                },
+            ],
+        },
+    );
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Vertex,
+        vec![naga::GlobalUse::STORE; 5],
+    );
+    let check = |limit| {
+        let limits = wgt::Limits {
+            max_inter_stage_shader_components: limit,
+            ..wgt::Limits::default()
+        };
+        check_stage(
+            &module,
+            &StageEnvironment {
+                limits,
+                ..StageEnvironment::default()
+            },
+            "main",
+            spirv::ExecutionModel::Vertex,
+            StageInterface::default(),
+        )
+    };
+
+    assert!(check(22).is_ok());
+    match check(16) {
+        Err(StageError::TooManyInterStageComponents {
+            used: 22,
+            limit: 16,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other.err()),
+    }
+}
+
+#[test]
+fn test_too_many_inter_stage_variables() {
+    let mut module = naga::Module::generate_empty();
+    for location in 0..5 {
+        add_global(
+            &mut module,
+            spirv::StorageClass::Output,
+            naga::Binding::Location(location),
+            naga::TypeInner::Scalar {
+                kind: naga::ScalarKind::Float,
+                width: 32,
+            },
+        );
+    }
+    add_entry_point(
+        &mut module,
+        spirv::ExecutionModel::Vertex,
+        vec![naga::GlobalUse::STORE; 5],
+    );
+    let check = |limit| {
+        let limits = wgt::Limits {
+            max_inter_stage_shader_variables: limit,
+            ..wgt::Limits::default()
+        };
+        check_stage(
+            &module,
+            &StageEnvironment {
+                limits,
+                ..StageEnvironment::default()
+            },
+            "main",
+            spirv::ExecutionModel::Vertex,
+            StageInterface::default(),
+        )
+    };
+
+    // Five scalars are well within the component limit, only their locations count here.
+    assert!(check(5).is_ok());
+    match check(4) {
+        Err(StageError::TooManyInterStageVariables { count: 5, limit: 4 }) => {}
+        other => panic!("unexpected result: {:?}", other.err()),
+    }
+}
+
+#[test]
+fn test_invalid_array_stride() {
+    let check = |stride| {
+        let mut module = naga::Module::generate_empty();
+        let vec4 = module.types.append(naga::Type {
+            name: None,
+            inner: naga::TypeInner::Vector {
+                size: naga::VectorSize::Quad,
+                kind: naga::ScalarKind::Float,
+                width: 32,
+            },
+        });
+        let array = module.types.append(naga::Type {
+            name: None,
+            inner: naga::TypeInner::Array {
+                base: vec4,
+                size: naga::ArraySize::Static(2),
+                stride: std::num::NonZeroU32::new(stride),
+            },
+        });
+        let var = add_global(
+            &mut module,
+            spirv::StorageClass::StorageBuffer,
+            naga::Binding::Descriptor { set: 0, binding: 0 },
+            naga::TypeInner::Struct {
+                members: vec![naga::StructMember {
+                    name: None,
+                    binding: None,
+                    ty: array,
+                    offset: 0,
+                }],
+            },
+        );
+        let entry = BindGroupLayoutEntry::new(
+            0,
+            wgt::ShaderStage::COMPUTE,
+            BindingType::StorageBuffer {
+                dynamic: false,
+                min_binding_size: None,
+                readonly: true,
+            },
+        );
+        check_binding(
+            &module,
+            &module.global_variables[var],
+            &entry,
+            naga::GlobalUse::LOAD,
+            wgt::ShaderStage::COMPUTE,
+            wgt::Features::empty(),
+            &wgt::Limits::default(),
+        )
+    };
+
+    assert!(check(16).is_ok());
+    match check(8) {
+        Err(BindingError::InvalidArrayStride {
+            stride: 8,
+            element_size: 16,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
@@ -241,6 +241,43 @@ bitflags::bitflags! {
         ///
         /// This is a native only feature.
         const PUSH_CONSTANTS = 0x0000_0000_0080_0000;
+        /// Allows storage textures that aren't read-only to be both read from and written to
+        /// in the same shader.
+        ///
+        /// Supported platforms:
+        /// - DX12
+        /// - Vulkan
+        /// - Metal (with MSL 1.2+)
+        ///
+        /// This is a native only feature.
+        const STORAGE_TEXTURE_READ_WRITE = 0x0000_0000_0100_0000;
+        /// Allows vertex shaders to write to storage buffers and storage textures.
+        ///
+        /// Supported platforms:
+        /// - DX12
+        /// - Vulkan
+        /// - Metal
+        ///
+        /// This is a native only feature.
+        const VERTEX_WRITABLE_STORAGE = 0x0000_0000_0200_0000;
+        /// Allows the user to use the `Double*` vertex formats, which are
+        /// `double` and `dvec*` in shaders.
+        ///
+        /// Supported platforms:
+        /// - Vulkan
+        ///
+        /// This is a native only feature.
+        const VERTEX_ATTRIBUTE_64BIT = 0x0000_0000_0400_0000;
+        /// Allows the use of [`BindingType::StorageTexelBuffer`]: buffers accessed from shaders
+        /// as one-dimensional images with a texel format.
+        ///
+        /// Supported platforms:
+        /// - DX12
+        /// - Vulkan
+        /// - Metal
+        ///
+        /// This is a native only feature.
+        const TEXEL_BUFFERS = 0x0000_0000_0800_0000;
         /// Features which are part of the upstream WebGPU standard.
         const ALL_WEBGPU = 0x0000_0000_0000_FFFF;
         /// Features that are only available when targeting native (not web).
@@ -293,6 +330,15 @@ pub struct Limits {
     /// - DX11 & OpenGL don't natively support push constants, and are emulated with uniforms,
     ///   so this number is less useful.
     pub max_push_constant_size: u32,
+    /// Amount of components, summed over all locations, that a vertex shader can pass to the fragment
+    /// shader. Defaults to 60. Higher is "better".
+    pub max_inter_stage_shader_components: u32,
+    /// Amount of distinct locations that a vertex shader can pass to the fragment shader. Defaults
+    /// to 16. Higher is "better".
+    pub max_inter_stage_shader_variables: u32,
+    /// Maximum size in bytes of the workgroup variables a compute shader uses. Defaults to 16384.
+    /// Higher is "better".
+    pub max_compute_workgroup_storage_size: u32,
 }
 
 impl Default for Limits {
@@ -308,6 +354,9 @@ impl Default for Limits {
             max_uniform_buffers_per_shader_stage: 12,
             max_uniform_buffer_binding_size: 16384,
             max_push_constant_size: 0,
+            max_inter_stage_shader_components: 60,
+            max_inter_stage_shader_variables: 16,
+            max_compute_workgroup_storage_size: 16384,
         }
     }
 }
@@ -645,6 +694,10 @@ pub enum TextureFormat {
     Depth24Plus = 36,
     /// Special depth/stencil format with at least 24 bit integer depth and 8 bits integer stencil.
     Depth24PlusStencil8 = 37,
+
+    // Packed shared exponent formats
+    /// Red, green, and blue channels. 9 bit mantissa per channel with a shared 5 bit exponent. Float in shader.
+    Rgb9e5Ufloat = 38,
 }
 
 bitflags::bitflags! {
@@ -939,6 +992,18 @@ pub enum VertexFormat {
     Int3 = 28,
     /// Four signed ints (i32). `ivec4` in shaders.
     Int4 = 29,
+    /// One double-precision float (f64). `double` in shaders. Requires
+    /// [`Features::VERTEX_ATTRIBUTE_64BIT`].
+    Double = 30,
+    /// Two double-precision floats (f64). `dvec2` in shaders. Requires
+    /// [`Features::VERTEX_ATTRIBUTE_64BIT`].
+    Double2 = 31,
+    /// Three double-precision floats (f64). `dvec3` in shaders. Requires
+    /// [`Features::VERTEX_ATTRIBUTE_64BIT`].
+    Double3 = 32,
+    /// Four double-precision floats (f64). `dvec4` in shaders. Requires
+    /// [`Features::VERTEX_ATTRIBUTE_64BIT`].
+    Double4 = 33,
 }
 
 impl VertexFormat {
@@ -967,9 +1032,15 @@ impl VertexFormat {
             | VertexFormat::Half4
             | VertexFormat::Float2
             | VertexFormat::Uint2
-            | VertexFormat::Int2 => 8,
+            | VertexFormat::Int2
+            | VertexFormat::Double => 8,
             VertexFormat::Float3 | VertexFormat::Uint3 | VertexFormat::Int3 => 12,
-            VertexFormat::Float4 | VertexFormat::Uint4 | VertexFormat::Int4 => 16,
+            VertexFormat::Float4
+            | VertexFormat::Uint4
+            | VertexFormat::Int4
+            | VertexFormat::Double2 => 16,
+            VertexFormat::Double3 => 24,
+            VertexFormat::Double4 => 32,
         }
     }
 }
@@ -1654,6 +1725,7 @@ impl From<TextureFormat> for TextureComponentType {
             | TextureFormat::Rg8Snorm
             | TextureFormat::Rg16Float
             | TextureFormat::Rg11b10Float
+            | TextureFormat::Rgb9e5Ufloat
             | TextureFormat::Rg32Float
             | TextureFormat::Rgba8Snorm
             | TextureFormat::Rgba16Float
@@ -1799,6 +1871,19 @@ pub enum BindingType {
         /// ```
         readonly: bool,
     },
+    /// A buffer accessed as a one-dimensional storage image.
+    ///
+    /// Example GLSL syntax:
+    /// ```cpp,ignore
+    /// layout(set=0, binding=0, r32f) uniform imageBuffer myTexelBuffer;
+    /// ```
+    /// The [`Features::TEXEL_BUFFERS`] feature is required for this binding type.
+    StorageTexelBuffer {
+        /// Format of the texels.
+        format: TextureFormat,
+        /// The buffer can only be read in the shader and it must be annotated with `readonly`.
+        readonly: bool,
+    },
 }
 
 /// Describes a single binding inside a bind group.